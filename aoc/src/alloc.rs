@@ -0,0 +1,134 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Counts what goes through the system allocator, the runner installs it as the global allocator
+// when built with the `count-allocations` feature
+pub struct CountingAllocator {
+    count: AtomicUsize,
+    bytes: AtomicUsize,
+    live: AtomicUsize,
+    peak: AtomicUsize,
+    // bytes that were live on the last reset
+    base: AtomicUsize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Allocations {
+    pub count: usize,
+    pub bytes: usize,
+    // most bytes live at once, not counting ones that were live before the reset
+    pub peak: usize,
+}
+
+impl CountingAllocator {
+    pub const fn new() -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+            bytes: AtomicUsize::new(0),
+            live: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            base: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn reset(&self) {
+        let live = self.live.load(Ordering::Relaxed);
+
+        self.count.store(0, Ordering::Relaxed);
+        self.bytes.store(0, Ordering::Relaxed);
+        self.peak.store(live, Ordering::Relaxed);
+        self.base.store(live, Ordering::Relaxed);
+    }
+
+    // Everything allocated since the last reset
+    pub fn allocations(&self) -> Allocations {
+        let peak = self.peak.load(Ordering::Relaxed);
+        let base = self.base.load(Ordering::Relaxed);
+
+        Allocations {
+            count: self.count.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            peak: peak.saturating_sub(base),
+        }
+    }
+
+    fn allocated(&self, size: usize) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(size, Ordering::Relaxed);
+
+        let live = self.live.fetch_add(size, Ordering::Relaxed) + size;
+        self.peak.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn freed(&self, size: usize) {
+        self.live.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+impl Default for CountingAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Resizing counts as freeing the old block and allocating a new one
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+
+        if !ptr.is_null() {
+            self.allocated(layout.size());
+        }
+
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        self.freed(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+
+        if !new_ptr.is_null() {
+            self.freed(layout.size());
+            self.allocated(new_size);
+        }
+
+        new_ptr
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_counting_allocator() {
+        let allocator = CountingAllocator::new();
+        let layout = Layout::from_size_align(64, 8).unwrap();
+
+        unsafe {
+            let kept = allocator.alloc(layout);
+            allocator.reset();
+
+            let a = allocator.alloc(layout);
+            let b = allocator.realloc(a, layout, 128);
+            allocator.dealloc(b, Layout::from_size_align(128, 8).unwrap());
+
+            let c = allocator.alloc(layout);
+            allocator.dealloc(c, layout);
+            allocator.dealloc(kept, layout);
+        }
+
+        assert_eq!(
+            allocator.allocations(),
+            Allocations {
+                count: 3,
+                bytes: 256,
+                peak: 128,
+            }
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use crate::solution::{Part, Solution};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+impl Stats {
+    // For even amount of samples median is the mean of two middle ones
+    pub fn new(samples: &mut [Duration]) -> Option<Self> {
+        samples.sort();
+
+        let min = *samples.first()?;
+        let middle = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[middle - 1] + samples[middle]) / 2
+        } else {
+            samples[middle]
+        };
+        let mean = samples.iter().sum::<Duration>() / samples.len() as u32;
+
+        Some(Self { min, median, mean })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+// Parses and solves the input `runs` times, timing both phases on their own
+pub fn measure<S: Solution>(
+    solution: &S,
+    part: Part,
+    input: &str,
+    runs: usize,
+) -> Result<Measurement, S::Error> {
+    let mut parse = vec![];
+    let mut solve = vec![];
+
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        let parsed = solution.parse(black_box(input))?;
+        parse.push(start.elapsed());
+
+        let start = Instant::now();
+        black_box(solution.solve_part(&parsed, part)?);
+        solve.push(start.elapsed());
+    }
+
+    Ok(Measurement {
+        parse: Stats::new(&mut parse).unwrap(),
+        solve: Stats::new(&mut solve).unwrap(),
+    })
+}
+
+// Medians of parse and solve of every day and part, saved to compare later runs against
+pub type Baseline = HashMap<(u32, u32), (Duration, Duration)>;
+
+// One line per part, like `5 2 1200 34000`, with times in nanoseconds
+pub fn format_baseline(measurements: &[(u32, u32, Measurement)]) -> String {
+    let mut baseline = String::new();
+
+    for (day, part, measurement) in measurements {
+        writeln!(
+            baseline,
+            "{} {} {} {}",
+            day,
+            part,
+            measurement.parse.median.as_nanos(),
+            measurement.solve.median.as_nanos()
+        )
+        .unwrap();
+    }
+
+    baseline
+}
+
+// On error returns the number of the wrong line
+pub fn parse_baseline(baseline: &str) -> Result<Baseline, usize> {
+    let mut result = Baseline::new();
+
+    for (n, line) in baseline.lines().enumerate() {
+        let numbers = line
+            .split_whitespace()
+            .map(str::parse::<u64>)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| n + 1)?;
+
+        let &[day, part, parse, solve] = numbers.as_slice() else {
+            return Err(n + 1);
+        };
+
+        result.insert(
+            (day as u32, part as u32),
+            (Duration::from_nanos(parse), Duration::from_nanos(solve)),
+        );
+    }
+
+    Ok(result)
+}
+
+// Relative change of `now` against `before`, like `+12.5%`
+pub fn change(before: Duration, now: Duration) -> String {
+    if before.is_zero() {
+        return "n/a".to_string();
+    }
+
+    let change = (now.as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0;
+    format!("{:+.1}%", change)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_stats() {
+        assert_eq!(
+            Stats::new(&mut [ms(5), ms(1), ms(3)]),
+            Some(Stats {
+                min: ms(1),
+                median: ms(3),
+                mean: ms(3),
+            })
+        );
+        assert_eq!(
+            Stats::new(&mut [ms(4), ms(1), ms(2), ms(9)]),
+            Some(Stats {
+                min: ms(1),
+                median: ms(3),
+                mean: ms(4),
+            })
+        );
+        assert_eq!(Stats::new(&mut []), None);
+    }
+
+    #[test]
+    fn test_baseline() {
+        let stats = |median| Stats {
+            min: ms(0),
+            median,
+            mean: ms(0),
+        };
+        let measurement = Measurement {
+            parse: stats(ms(2)),
+            solve: stats(Duration::from_nanos(1500)),
+        };
+
+        let baseline = format_baseline(&[(5, 2, measurement)]);
+
+        assert_eq!(baseline, "5 2 2000000 1500\n");
+        assert_eq!(
+            parse_baseline(&baseline),
+            Ok(Baseline::from([(
+                (5, 2),
+                (ms(2), Duration::from_nanos(1500))
+            )]))
+        );
+        assert_eq!(parse_baseline("5 2 1 1\n5 x 1 1"), Err(2));
+        assert_eq!(parse_baseline("5 2 1"), Err(1));
+    }
+
+    #[test]
+    fn test_change() {
+        assert_eq!(change(ms(4), ms(5)), "+25.0%");
+        assert_eq!(change(ms(4), ms(3)), "-25.0%");
+        assert_eq!(change(ms(0), ms(3)), "n/a");
+    }
+}
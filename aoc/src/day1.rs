@@ -0,0 +1,309 @@
+use crate::diagnostic::Diagnostic;
+use crate::input;
+use crate::pattern_matcher::{MultiPatternMatcher, PatternMatcher};
+use crate::solution::{Part, Solution, Solved, Solving};
+use std::io::{self, BufRead};
+use std::ops::ControlFlow;
+
+#[derive(Debug, PartialEq)]
+pub struct LineWithOutNumber {
+    pub line: usize,
+}
+
+impl From<LineWithOutNumber> for Diagnostic {
+    fn from(e: LineWithOutNumber) -> Self {
+        Diagnostic::new("line has no digit").at_line(e.line)
+    }
+}
+
+// Calibration value is made of the first and the last digit in the line
+fn calibration_value(line: &str) -> Option<u32> {
+    let first = line.chars().find_map(|c| c.to_digit(10))?;
+    let last = line.chars().rev().find_map(|c| c.to_digit(10))?;
+
+    Some(first * 10 + last)
+}
+
+const DIGITS: [(&str, u32); 19] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+// Digits can be also spelled out with letters, matchers are kept to be reused for every line
+struct SpelledDigits {
+    matcher: MultiPatternMatcher,
+    matcher_rev: MultiPatternMatcher,
+}
+
+impl SpelledDigits {
+    fn new() -> Self {
+        let matcher = MultiPatternMatcher::new(&DIGITS);
+        let matcher_rev = matcher.reverse();
+
+        Self {
+            matcher,
+            matcher_rev,
+        }
+    }
+
+    fn calibration_value(&mut self, line: &str) -> Option<u32> {
+        let first = read_string_unitl_pattern_matched(line.chars(), &mut self.matcher);
+        let last = read_string_unitl_pattern_matched(line.chars().rev(), &mut self.matcher_rev);
+
+        self.matcher.reset();
+        self.matcher_rev.reset();
+
+        Some(first? * 10 + last?)
+    }
+}
+
+// First version of `SpelledDigits` with one KMP matcher for every pattern, only kept to compare
+// them in benchmarks
+struct KmpDigits {
+    matchers: Vec<PatternMatcher>,
+    matchers_rev: Vec<PatternMatcher>,
+}
+
+impl KmpDigits {
+    fn new() -> Self {
+        let matchers = DIGITS
+            .iter()
+            .map(|&(pattern, value)| PatternMatcher::new(pattern, value))
+            .collect::<Vec<_>>();
+        let matchers_rev = matchers.iter().map(PatternMatcher::reverse).collect();
+
+        Self {
+            matchers,
+            matchers_rev,
+        }
+    }
+
+    fn calibration_value(&mut self, line: &str) -> Option<u32> {
+        let first = first_match(line.chars(), &mut self.matchers);
+        let last = first_match(line.chars().rev(), &mut self.matchers_rev);
+
+        for matcher in self.matchers.iter_mut().chain(&mut self.matchers_rev) {
+            matcher.reset();
+        }
+
+        Some(first? * 10 + last?)
+    }
+}
+
+// Matchers are checked in order, so the first one that matches on a char wins
+fn first_match(chars: impl Iterator<Item = char>, matchers: &mut [PatternMatcher]) -> Option<u32> {
+    for c in chars {
+        for matcher in matchers.iter_mut() {
+            if matcher.matches(c) {
+                return Some(matcher.value());
+            }
+        }
+    }
+
+    None
+}
+
+fn line_value(value: Option<u32>, line: usize) -> Result<u32, LineWithOutNumber> {
+    value.ok_or(LineWithOutNumber { line })
+}
+
+pub fn calibration_values(text: &str) -> impl Iterator<Item = Result<u32, LineWithOutNumber>> + '_ {
+    text.lines()
+        .enumerate()
+        .map(|(n, line)| line_value(calibration_value(line), n + 1))
+}
+
+// Same as `calibration_values`, but digits can be also spelled out with letters
+pub fn spelled_calibration_values(
+    text: &str,
+) -> impl Iterator<Item = Result<u32, LineWithOutNumber>> + '_ {
+    let mut digits = SpelledDigits::new();
+
+    text.lines()
+        .enumerate()
+        .map(move |(n, line)| line_value(digits.calibration_value(line), n + 1))
+}
+
+// Uses Iterator to avoid allocating a new string
+fn read_string_unitl_pattern_matched(
+    mut chars: impl Iterator<Item = char>,
+    matcher: &mut MultiPatternMatcher,
+) -> Option<u32> {
+    chars
+        .find_map(|c| matcher.matches(c))
+        .or_else(|| matcher.finish())
+}
+
+// Every line adds at most 99, so the sum can't get past 64 bits
+fn sum(
+    mut values: impl Iterator<Item = Result<u32, LineWithOutNumber>>,
+) -> Result<u64, LineWithOutNumber> {
+    values.try_fold(0, |acc, value| value.map(|value| acc + u64::from(value)))
+}
+
+pub fn part1(text: &str) -> Result<u64, LineWithOutNumber> {
+    sum(calibration_values(text))
+}
+
+pub fn part2(text: &str) -> Result<u64, LineWithOutNumber> {
+    sum(spelled_calibration_values(text))
+}
+
+// Same as `part2` with `KmpDigits`
+pub fn part2_kmp(text: &str) -> Result<u64, LineWithOutNumber> {
+    let mut digits = KmpDigits::new();
+
+    sum(text
+        .lines()
+        .enumerate()
+        .map(|(n, line)| line_value(digits.calibration_value(line), n + 1)))
+}
+
+pub struct Day1;
+
+// Lines are only checked while solving, so input is kept as it is
+impl Solution for Day1 {
+    const DAY: u32 = 1;
+
+    type Input = String;
+    type Answer = u64;
+    type Error = LineWithOutNumber;
+
+    fn parse(&self, input: &str) -> Result<String, LineWithOutNumber> {
+        Ok(input.to_string())
+    }
+
+    fn part1(&self, input: &String) -> Result<u64, LineWithOutNumber> {
+        part1(input)
+    }
+
+    fn part2(&self, input: &String) -> Result<u64, LineWithOutNumber> {
+        part2(input)
+    }
+
+    fn solve_reader(
+        &self,
+        reader: &mut dyn BufRead,
+        part: Part,
+        lenient: bool,
+    ) -> Option<io::Result<Solved<u64, LineWithOutNumber>>> {
+        // matchers are only built when they are needed, so part one allocates nothing for them
+        let mut digits = (part == Part::Two).then(SpelledDigits::new);
+        let mut solving = Solving::new(lenient);
+        let mut sum = 0;
+
+        let read = input::for_each_line(reader, |n, line| {
+            let value = match digits.as_mut() {
+                None => calibration_value(line),
+                Some(digits) => digits.calibration_value(line),
+            };
+
+            match line_value(value, n) {
+                Ok(value) => {
+                    sum += u64::from(value);
+                    ControlFlow::Continue(())
+                }
+                Err(e) => solving.reject(e, line),
+            }
+        });
+
+        Some(read.map(|()| solving.finish(sum)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calibration_values() {
+        let input = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet";
+
+        let mut values = calibration_values(input);
+
+        assert_eq!(values.next(), Some(Ok(12)));
+        assert_eq!(values.next(), Some(Ok(38)));
+        assert_eq!(values.next(), Some(Ok(15)));
+        assert_eq!(values.next(), Some(Ok(77)));
+        assert_eq!(values.next(), None);
+    }
+
+    #[test]
+    fn test_spelled_calibration_values() {
+        let input = "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2\nzoneight234\n7pqrstsixteen";
+
+        let mut values = spelled_calibration_values(input);
+
+        assert_eq!(values.next(), Some(Ok(29)));
+        assert_eq!(values.next(), Some(Ok(83)));
+        assert_eq!(values.next(), Some(Ok(13)));
+        assert_eq!(values.next(), Some(Ok(24)));
+        assert_eq!(values.next(), Some(Ok(42)));
+        assert_eq!(values.next(), Some(Ok(14)));
+        assert_eq!(values.next(), Some(Ok(76)));
+        assert_eq!(values.next(), None);
+    }
+
+    #[test]
+    fn test_part2_kmp() {
+        let input = "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2\nzoneight234\n7pqrstsixteen";
+
+        assert_eq!(part2_kmp(input), part2(input));
+        assert_eq!(part2_kmp(input), Ok(281));
+        assert_eq!(part2_kmp("1\nabc"), Err(LineWithOutNumber { line: 2 }));
+    }
+
+    #[test]
+    fn test_sum_past_32_bits() {
+        let values = [Ok(u32::MAX), Ok(u32::MAX)].into_iter();
+
+        assert_eq!(sum(values), Ok(2 * u64::from(u32::MAX)));
+    }
+
+    #[test]
+    fn test_lenient_skips_lines_without_number() {
+        assert_eq!(
+            Day1.solve_lenient("1abc2\nabc\n3\nxyz", Part::One),
+            Some((
+                Some(45),
+                vec![LineWithOutNumber { line: 2 }, LineWithOutNumber { line: 4 },]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_part1_stops_on_line_without_number() {
+        assert_eq!(part1("1abc2\nabc\n3"), Err(LineWithOutNumber { line: 2 }));
+    }
+
+    #[test]
+    fn test_reader_stops_on_line_without_number() {
+        let solved = Day1
+            .solve_reader(&mut "1abc2\nabc\n3".as_bytes(), Part::One, false)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(solved.answer, None);
+        assert_eq!(
+            solved.rejected,
+            vec![(LineWithOutNumber { line: 2 }, "abc".to_string())]
+        );
+    }
+}
@@ -0,0 +1,624 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead};
+use std::ops::{ControlFlow, Range};
+use std::str::FromStr;
+
+use crate::diagnostic::{columns_of, Diagnostic};
+use crate::input;
+use crate::solution::{Part, Solution, Solved, Solving};
+
+#[derive(Debug, PartialEq)]
+pub enum WrongGameFormat {
+    // columns of the part of the line that could not be read
+    Malformed {
+        line: usize,
+        columns: Range<usize>,
+    },
+    UnknownColor {
+        color: String,
+        line: usize,
+        columns: Range<usize>,
+    },
+    // answer doesn't fit in 64 bits once the game on `line` is counted
+    Overflow {
+        line: usize,
+    },
+}
+
+impl From<WrongGameFormat> for Diagnostic {
+    fn from(e: WrongGameFormat) -> Self {
+        match e {
+            WrongGameFormat::Malformed { line, columns } => Diagnostic::new("malformed game")
+                .at_line(line)
+                .at_columns(columns),
+            WrongGameFormat::UnknownColor {
+                color,
+                line,
+                columns,
+            } => Diagnostic::new(format!("color `{}` is not in the bag", color))
+                .at_line(line)
+                .at_columns(columns),
+            WrongGameFormat::Overflow { line } => {
+                Diagnostic::new("answer doesn't fit in 64 bits").at_line(line)
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WrongBagFormat(pub String);
+
+// Limit of cubes of every color that are in the bag
+pub type Bag = HashMap<String, u32>;
+
+pub fn default_bag() -> Bag {
+    Bag::from([
+        ("red".to_string(), 12),
+        ("green".to_string(), 13),
+        ("blue".to_string(), 14),
+    ])
+}
+
+// Parses entries like `red=12`
+pub fn parse_bag<'a>(entries: impl Iterator<Item = &'a str>) -> Result<Bag, WrongBagFormat> {
+    let mut bag = Bag::new();
+
+    for entry in entries {
+        let limit = entry
+            .split_once('=')
+            .and_then(|(color, limit)| Some((color, limit.parse::<u32>().ok()?)))
+            .filter(|(color, _)| !color.is_empty());
+
+        match limit {
+            Some((color, limit)) if bag.insert(color.to_string(), limit).is_none() => {}
+            _ => return Err(WrongBagFormat(entry.to_string())),
+        }
+    }
+
+    Ok(bag)
+}
+
+// Bag is given either as arguments like `red=12 green=13 blue=14`, or as `--config path` to a file
+// with the same entries, without arguments the bag from the puzzle is used
+pub fn bag_from_args(args: &[String]) -> Result<Bag, WrongBagFormat> {
+    match args {
+        [] => Ok(default_bag()),
+        [flag, path] if flag == "--config" => {
+            let config = fs::read_to_string(path).map_err(|e| WrongBagFormat(e.to_string()))?;
+            parse_bag(config.split_whitespace())
+        }
+        entries => parse_bag(entries.iter().map(String::as_str)),
+    }
+}
+
+fn is_bag_entry(arg: &str) -> bool {
+    arg.split_once('=')
+        .is_some_and(|(color, limit)| !color.is_empty() && limit.parse::<u32>().is_ok())
+}
+
+// Bag entries are the ones like `red=12` or `--config` with the path after it, the rest of arguments
+// are paths of inputs, so a path can have `=` in it
+pub fn split_bag_args(args: Vec<String>) -> (Vec<String>, Vec<String>) {
+    let mut bag = vec![];
+    let mut paths = vec![];
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            bag.push(arg);
+            bag.extend(args.next());
+        } else if is_bag_entry(&arg) {
+            bag.push(arg);
+        } else {
+            paths.push(arg);
+        }
+    }
+
+    (bag, paths)
+}
+
+// Cubes of one color shown in a round
+#[derive(Debug, PartialEq)]
+pub struct Cubes {
+    pub color: String,
+    pub count: u32,
+    // columns of the color in the line, so an unknown one can be pointed at
+    pub columns: Range<usize>,
+}
+
+// Cubes of every color shown in one round, in the order of the line
+pub type Round = Vec<Cubes>;
+
+#[derive(Debug, PartialEq)]
+pub struct Game {
+    pub id: u32,
+    pub rounds: Vec<Round>,
+}
+
+impl Game {
+    // `line` is only used to report errors
+    pub fn parse_line(game: &str, line: usize) -> Result<Self, WrongGameFormat> {
+        let malformed = |part| WrongGameFormat::Malformed {
+            line,
+            columns: columns_of(game, part),
+        };
+
+        let (header, rounds) = game.split_once(':').ok_or_else(|| malformed(game))?;
+        let id = header
+            .strip_prefix("Game ")
+            .and_then(|id| id.parse::<u32>().ok())
+            .ok_or_else(|| malformed(header))?;
+
+        let rounds = rounds
+            .split(';')
+            .map(|round| parse_round(game, round).map_err(malformed))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { id, rounds })
+    }
+}
+
+impl FromStr for Game {
+    type Err = WrongGameFormat;
+
+    fn from_str(game: &str) -> Result<Self, Self::Err> {
+        Self::parse_line(game, 1)
+    }
+}
+
+// `round` is a slice of `game`, on error returns the pair of count and color that is wrong, a color
+// that is shown twice keeps the larger count
+fn parse_round<'a>(game: &str, round: &'a str) -> Result<Round, &'a str> {
+    let mut result = Round::new();
+
+    for pair in round.split(',') {
+        let pair = pair.trim();
+        let mut words = pair.split_whitespace();
+        let count = words.next().and_then(|count| count.parse::<u32>().ok());
+        let color = words.next();
+
+        match (count, color, words.next()) {
+            (Some(count), Some(color), None) => {
+                match result.iter_mut().find(|cubes| cubes.color == color) {
+                    Some(shown) => shown.count = shown.count.max(count),
+                    None => result.push(Cubes {
+                        color: color.to_string(),
+                        count,
+                        columns: columns_of(game, color),
+                    }),
+                }
+            }
+            _ => return Err(pair),
+        }
+    }
+
+    Ok(result)
+}
+
+pub fn parse_games(games: &str) -> Result<Vec<Game>, WrongGameFormat> {
+    games
+        .lines()
+        .enumerate()
+        .map(|(n, game)| Game::parse_line(game, n + 1))
+        .collect()
+}
+
+fn add(sum: u64, value: u64, line: usize) -> Result<u64, WrongGameFormat> {
+    sum.checked_add(value)
+        .ok_or(WrongGameFormat::Overflow { line })
+}
+
+pub fn part1(games: &[Game], bag: &Bag) -> Result<u64, WrongGameFormat> {
+    let mut sum = 0;
+    for (n, game) in games.iter().enumerate() {
+        sum = add(sum, value_of_possible_game(game, bag, n + 1)?, n + 1)?;
+    }
+
+    Ok(sum)
+}
+
+// Colors are checked before limits, so the first unknown color is reported even when a game is
+// impossible because of an earlier one
+fn check_colors(game: &Game, bag: &Bag, line: usize) -> Result<(), WrongGameFormat> {
+    match game
+        .rounds
+        .iter()
+        .flatten()
+        .find(|cubes| !bag.contains_key(&cubes.color))
+    {
+        Some(cubes) => Err(WrongGameFormat::UnknownColor {
+            color: cubes.color.clone(),
+            line,
+            columns: cubes.columns.clone(),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn is_game_possible(game: &Game, bag: &Bag, line: usize) -> Result<bool, WrongGameFormat> {
+    check_colors(game, bag, line)?;
+
+    Ok(game
+        .rounds
+        .iter()
+        .flatten()
+        .all(|cubes| cubes.count <= bag[&cubes.color]))
+}
+
+// Id of the game when it's possible, otherwise 0
+fn value_of_possible_game(game: &Game, bag: &Bag, line: usize) -> Result<u64, WrongGameFormat> {
+    match is_game_possible(game, bag, line)? {
+        true => Ok(u64::from(game.id)),
+        false => Ok(0),
+    }
+}
+
+// Smallest amount of cubes of every color that makes the game possible, multiplied together
+fn power_of_game(game: &Game, bag: &Bag, line: usize) -> Result<u64, WrongGameFormat> {
+    check_colors(game, bag, line)?;
+
+    let mut minimal = HashMap::new();
+
+    for cubes in game.rounds.iter().flatten() {
+        let max = minimal.entry(&cubes.color).or_insert(0);
+        *max = cubes.count.max(*max);
+    }
+
+    let counts = bag
+        .keys()
+        .map(|color| u64::from(minimal.get(color).copied().unwrap_or(0)))
+        .collect::<Vec<_>>();
+
+    // a color that wasn't shown makes the power 0 even when the rest would overflow
+    if counts.contains(&0) {
+        return Ok(0);
+    }
+
+    counts
+        .into_iter()
+        .try_fold(1, u64::checked_mul)
+        .ok_or(WrongGameFormat::Overflow { line })
+}
+
+pub fn part2(games: &[Game], bag: &Bag) -> Result<u64, WrongGameFormat> {
+    let mut sum = 0;
+    for (n, game) in games.iter().enumerate() {
+        sum = add(sum, power_of_game(game, bag, n + 1)?, n + 1)?;
+    }
+
+    Ok(sum)
+}
+
+pub struct Day2 {
+    pub bag: Bag,
+}
+
+impl Default for Day2 {
+    fn default() -> Self {
+        Self { bag: default_bag() }
+    }
+}
+
+impl Solution for Day2 {
+    const DAY: u32 = 2;
+
+    type Input = Vec<Game>;
+    type Answer = u64;
+    type Error = WrongGameFormat;
+
+    fn parse(&self, input: &str) -> Result<Vec<Game>, WrongGameFormat> {
+        parse_games(input)
+    }
+
+    fn part1(&self, games: &Vec<Game>) -> Result<u64, WrongGameFormat> {
+        part1(games, &self.bag)
+    }
+
+    fn part2(&self, games: &Vec<Game>) -> Result<u64, WrongGameFormat> {
+        part2(games, &self.bag)
+    }
+
+    fn solve_reader(
+        &self,
+        reader: &mut dyn BufRead,
+        part: Part,
+        lenient: bool,
+    ) -> Option<io::Result<Solved<u64, WrongGameFormat>>> {
+        let mut solving = Solving::new(lenient);
+        let mut sum = 0;
+
+        let read = input::for_each_line(reader, |line, text| {
+            let value =
+                solving
+                    .parse(|| Game::parse_line(text, line))
+                    .and_then(|game| match part {
+                        Part::One => value_of_possible_game(&game, &self.bag, line),
+                        Part::Two => power_of_game(&game, &self.bag, line),
+                    });
+
+            match value.and_then(|value| add(sum, value, line)) {
+                Ok(total) => {
+                    sum = total;
+                    ControlFlow::Continue(())
+                }
+                // skipping the game wouldn't make the answer right
+                Err(e @ WrongGameFormat::Overflow { .. }) => solving.stop(e, text),
+                Err(e) => solving.reject(e, text),
+            }
+        });
+
+        Some(read.map(|()| solving.finish(sum)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sum_of_possible_games() {
+        let game = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+        assert_eq!(part1(&parse_games(game).unwrap(), &default_bag()), Ok(8));
+    }
+
+    #[test]
+    fn test_sum_of_possible_games_uses_game_id() {
+        let game = "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 12: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
+
+        assert_eq!(part1(&parse_games(game).unwrap(), &default_bag()), Ok(17));
+    }
+
+    #[test]
+    fn test_sum_of_possible_games_with_custom_bag() {
+        let game = "Game 1: 3 blue, 4 yellow; 1 yellow
+Game 2: 5 yellow";
+
+        let bag = bag_from_args(&["blue=3".to_string(), "yellow=4".to_string()]).unwrap();
+
+        assert_eq!(part1(&parse_games(game).unwrap(), &bag), Ok(1));
+        assert_eq!(
+            part1(
+                &parse_games(&format!("{game}\nGame 3: 1 purple")).unwrap(),
+                &bag
+            ),
+            Err(WrongGameFormat::UnknownColor {
+                color: "purple".to_string(),
+                line: 3,
+                columns: 11..17
+            })
+        );
+    }
+
+    #[test]
+    fn test_unknown_color_is_reported_before_limits() {
+        let games = parse_games("Game 1: 20 red, 1 purple, 2 blue, 3 green").unwrap();
+        let purple = Err(WrongGameFormat::UnknownColor {
+            color: "purple".to_string(),
+            line: 1,
+            columns: 19..25,
+        });
+
+        assert_eq!(part1(&games, &default_bag()), purple);
+        assert_eq!(part2(&games, &default_bag()), purple);
+
+        let games = parse_games("Game 1: 1 red; 2 purple, 1 pink, 3 black").unwrap();
+        let purple = Err(WrongGameFormat::UnknownColor {
+            color: "purple".to_string(),
+            line: 1,
+            columns: 18..24,
+        });
+
+        assert_eq!(part1(&games, &default_bag()), purple);
+        assert_eq!(part2(&games, &default_bag()), purple);
+    }
+
+    #[test]
+    fn test_lenient_skips_wrong_games() {
+        let game = "Game 1: 3 blue, 4 red
+Game 2: 3 blue, red
+Game 3: 1 purple
+Game 4: 2 green";
+
+        let (sum, rejected) = Day2::default().solve_lenient(game, Part::One).unwrap();
+
+        assert_eq!(sum, Some(5));
+        assert_eq!(
+            rejected,
+            vec![
+                WrongGameFormat::Malformed {
+                    line: 2,
+                    columns: 17..20
+                },
+                WrongGameFormat::UnknownColor {
+                    color: "purple".to_string(),
+                    line: 3,
+                    columns: 11..17
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_bag() {
+        assert_eq!(
+            parse_bag("red=1 blue=2".split_whitespace()),
+            Ok(Bag::from([("red".to_string(), 1), ("blue".to_string(), 2)]))
+        );
+        assert_eq!(
+            parse_bag(["red=x"].into_iter()),
+            Err(WrongBagFormat("red=x".to_string()))
+        );
+        assert_eq!(
+            parse_bag(["red=1", "red=2"].into_iter()),
+            Err(WrongBagFormat("red=2".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_game() {
+        let game = "Game 7: 3 blue, 4 red; 2 green".parse::<Game>();
+
+        assert_eq!(
+            game,
+            Ok(Game {
+                id: 7,
+                rounds: vec![
+                    Round::from([
+                        Cubes {
+                            color: "blue".to_string(),
+                            count: 3,
+                            columns: 11..15
+                        },
+                        Cubes {
+                            color: "red".to_string(),
+                            count: 4,
+                            columns: 19..22
+                        }
+                    ]),
+                    Round::from([Cubes {
+                        color: "green".to_string(),
+                        count: 2,
+                        columns: 26..31
+                    }]),
+                ],
+            })
+        );
+        assert_eq!(
+            "Gam 7: 3 blue".parse::<Game>(),
+            Err(WrongGameFormat::Malformed {
+                line: 1,
+                columns: 1..6
+            })
+        );
+        assert_eq!(
+            "Game x: 3 blue".parse::<Game>(),
+            Err(WrongGameFormat::Malformed {
+                line: 1,
+                columns: 1..7
+            })
+        );
+        assert_eq!(
+            Game::parse_line("Game 7: 3 blue, 4 red; 2 green 1", 4),
+            Err(WrongGameFormat::Malformed {
+                line: 4,
+                columns: 24..33
+            })
+        );
+    }
+
+    #[test]
+    fn test_sum_of_powers_of_games() {
+        let game = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+        assert_eq!(part2(&parse_games(game).unwrap(), &default_bag()), Ok(2286));
+    }
+
+    #[test]
+    fn test_sum_of_powers_of_games_with_custom_colors() {
+        let game = "Game 1: 3 blue, 4 yellow; 1 yellow, 2 blue
+Game 2: 5 yellow";
+
+        let bag = bag_from_args(&["blue=3".to_string(), "yellow=4".to_string()]).unwrap();
+
+        assert_eq!(part2(&parse_games(game).unwrap(), &bag), Ok(12));
+        assert_eq!(
+            part2(&parse_games(game).unwrap(), &default_bag()),
+            Err(WrongGameFormat::UnknownColor {
+                color: "yellow".to_string(),
+                line: 1,
+                columns: 19..25
+            })
+        );
+    }
+
+    #[test]
+    fn test_color_shown_twice_in_a_round() {
+        let games = parse_games("Game 1: 1 red, 13 red, 2 red").unwrap();
+
+        assert_eq!(
+            games[0].rounds,
+            vec![Round::from([Cubes {
+                color: "red".to_string(),
+                count: 13,
+                columns: 11..14
+            }])]
+        );
+        assert_eq!(part1(&games, &default_bag()), Ok(0));
+        assert_eq!(part2(&games, &default_bag()), Ok(0));
+    }
+
+    #[test]
+    fn test_answers_past_32_bits() {
+        let games = "Game 4294967295: 1 red\nGame 4294967295: 2 red\n";
+
+        assert_eq!(
+            Day2::default().solve(games, Part::One),
+            Ok(2 * u64::from(u32::MAX))
+        );
+
+        let games = "Game 1: 100000 red, 100000 green, 100000 blue";
+        let bag = bag_from_args(&[
+            "red=100000".to_string(),
+            "green=100000".to_string(),
+            "blue=100000".to_string(),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            Day2 { bag }.solve(games, Part::Two),
+            Ok(1_000_000_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_overflow_stops_also_in_lenient_mode() {
+        let big = u32::MAX;
+        let games = format!(
+            "Game 1: 1 red, 1 green, 1 blue\nGame 2: {big} red, {big} green, {big} blue\nGame 3: 1 red"
+        );
+
+        assert_eq!(
+            Day2::default().solve(&games, Part::Two),
+            Err(WrongGameFormat::Overflow { line: 2 })
+        );
+        assert_eq!(
+            Day2::default().solve_lenient(&games, Part::Two),
+            Some((None, vec![WrongGameFormat::Overflow { line: 2 }]))
+        );
+        // a color that is not shown is checked before multiplying
+        assert_eq!(
+            Day2::default().solve(&format!("Game 1: {big} red, {big} green"), Part::Two),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn test_split_bag_args() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            split_bag_args(args(&["red=1", "a.txt", "blue=2", "-"])),
+            (args(&["red=1", "blue=2"]), args(&["a.txt", "-"]))
+        );
+        assert_eq!(
+            split_bag_args(args(&["--config", "bag.txt", "a.txt"])),
+            (args(&["--config", "bag.txt"]), args(&["a.txt"]))
+        );
+        assert_eq!(
+            split_bag_args(args(&["runs/a=b.txt", "red=1", "=2"])),
+            (args(&["red=1"]), args(&["runs/a=b.txt", "=2"]))
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::BTreeSet;
+
+use crate::diagnostic::Diagnostic;
+use crate::solution::Solution;
+
+#[derive(Debug, PartialEq)]
+pub struct Number {
+    pub value: u32,
+    pub row: usize,
+    // columns of the first digit and one after the last digit
+    pub col_start: usize,
+    pub col_end: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Symbol {
+    pub symbol: char,
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RaggedRow {
+    pub line: usize,
+    pub len: usize,
+    pub expected: usize,
+}
+
+// Underlines cells that are missing or too many
+impl From<RaggedRow> for Diagnostic {
+    fn from(e: RaggedRow) -> Self {
+        Diagnostic::new(format!("row has {} cells, expected {}", e.len, e.expected))
+            .at_line(e.line)
+            .at_columns(e.len.min(e.expected) + 1..e.len.max(e.expected) + 1)
+    }
+}
+
+#[derive(Debug)]
+pub struct Schematic {
+    pub numbers: Vec<Number>,
+    pub symbols: Vec<Symbol>,
+    // id of the number, index in `numbers`, that covers every cell of the grid
+    cells: Vec<Vec<Option<usize>>>,
+}
+
+impl Schematic {
+    pub fn parse(engine: &str) -> Self {
+        let mut numbers = vec![];
+        let mut symbols = vec![];
+        let mut cells = vec![];
+
+        for (row, line) in engine.lines().enumerate() {
+            let line = line.chars().collect::<Vec<_>>();
+            let mut row_cells = vec![None; line.len()];
+            let mut col = 0;
+
+            while col < line.len() {
+                match line[col] {
+                    '.' => col += 1,
+                    c if c.is_ascii_digit() => {
+                        let col_start = col;
+                        let mut value = 0;
+
+                        while let Some(digit) = line.get(col).and_then(|c| c.to_digit(10)) {
+                            value = value * 10 + digit;
+                            row_cells[col] = Some(numbers.len());
+                            col += 1;
+                        }
+
+                        numbers.push(Number {
+                            value,
+                            row,
+                            col_start,
+                            col_end: col,
+                        });
+                    }
+                    symbol => {
+                        symbols.push(Symbol { symbol, row, col });
+                        col += 1;
+                    }
+                }
+            }
+
+            cells.push(row_cells);
+        }
+
+        Self {
+            numbers,
+            symbols,
+            cells,
+        }
+    }
+
+    // Same as parse, but rejects schematics whose rows are not as long as the first one
+    pub fn parse_rectangular(engine: &str) -> Result<Self, RaggedRow> {
+        let mut lines = engine.lines().map(|line| line.chars().count());
+        let expected = lines.next().unwrap_or(0);
+
+        if let Some((n, len)) = lines.enumerate().find(|(_, len)| *len != expected) {
+            return Err(RaggedRow {
+                line: n + 2,
+                len,
+                expected,
+            });
+        }
+
+        Ok(Self::parse(engine))
+    }
+
+    // Cells outside of the grid, also ones missing in rows shorter than others, are empty
+    pub fn number_at(&self, row: usize, col: usize) -> Option<usize> {
+        *self.cells.get(row)?.get(col)?
+    }
+
+    // Ids of all distinct numbers that touch the symbol, also diagonally
+    pub fn numbers_around(&self, symbol: &Symbol) -> BTreeSet<usize> {
+        let mut result = BTreeSet::new();
+
+        for row in symbol.row.saturating_sub(1)..=symbol.row + 1 {
+            for col in symbol.col.saturating_sub(1)..=symbol.col + 1 {
+                result.extend(self.number_at(row, col));
+            }
+        }
+
+        result
+    }
+}
+
+pub fn part1(schematic: &Schematic) -> u32 {
+    schematic
+        .symbols
+        .iter()
+        .flat_map(|symbol| schematic.numbers_around(symbol))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|id| schematic.numbers[id].value)
+        .sum()
+}
+
+pub fn part2(schematic: &Schematic) -> u32 {
+    schematic
+        .symbols
+        .iter()
+        .filter(|symbol| symbol.symbol == '*')
+        .map(|symbol| schematic.numbers_around(symbol))
+        .filter(|numbers| numbers.len() == 2)
+        .map(|numbers| {
+            numbers
+                .into_iter()
+                .map(|id| schematic.numbers[id].value)
+                .product::<u32>()
+        })
+        .sum()
+}
+
+// Strict solution rejects ragged schematics instead of treating missing cells as empty
+#[derive(Default)]
+pub struct Day3 {
+    pub strict: bool,
+}
+
+impl Solution for Day3 {
+    const DAY: u32 = 3;
+
+    type Input = Schematic;
+    type Answer = u32;
+    type Error = RaggedRow;
+
+    fn parse(&self, input: &str) -> Result<Schematic, RaggedRow> {
+        if self.strict {
+            Schematic::parse_rectangular(input)
+        } else {
+            Ok(Schematic::parse(input))
+        }
+    }
+
+    fn part1(&self, schematic: &Schematic) -> Result<u32, RaggedRow> {
+        Ok(part1(schematic))
+    }
+
+    fn part2(&self, schematic: &Schematic) -> Result<u32, RaggedRow> {
+        Ok(part2(schematic))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_part1_example() {
+        let game = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+        assert_eq!(part1(&Schematic::parse(game)), 4361);
+    }
+
+    #[test]
+    fn test_parse_schematic() {
+        let schematic = Schematic::parse("...\n.12\n*..");
+
+        assert_eq!(
+            schematic.numbers,
+            vec![Number {
+                value: 12,
+                row: 1,
+                col_start: 1,
+                col_end: 3
+            }]
+        );
+        assert_eq!(
+            schematic.symbols,
+            vec![Symbol {
+                symbol: '*',
+                row: 2,
+                col: 0
+            }]
+        );
+        assert_eq!(schematic.number_at(1, 1), Some(0));
+        assert_eq!(schematic.number_at(1, 2), Some(0));
+        assert_eq!(schematic.number_at(1, 0), None);
+    }
+
+    #[test]
+    fn test_numbers_around_counts_every_number_once() {
+        let schematic = Schematic::parse("123\n.#.\n4.5");
+
+        assert_eq!(
+            schematic.numbers_around(&schematic.symbols[0]),
+            BTreeSet::from([0, 1, 2])
+        );
+    }
+
+    #[test]
+    fn test_part1_ragged_rows_count_missing_cells_as_empty() {
+        let game = "..\n.#12\n4\n...*\n..7";
+
+        assert_eq!(part1(&Schematic::parse(game)), 23);
+    }
+
+    #[test]
+    fn test_parse_rectangular_rejects_ragged_rows() {
+        assert!(Schematic::parse_rectangular("...\n.1.\n...").is_ok());
+        assert_eq!(
+            Schematic::parse_rectangular("...\n.1.\n..").unwrap_err(),
+            RaggedRow {
+                line: 3,
+                len: 2,
+                expected: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_part2_example() {
+        let game = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+        assert_eq!(part2(&Schematic::parse(game)), 467835);
+    }
+
+    #[test]
+    fn test_part2_ragged_rows_count_missing_cells_as_empty() {
+        let game = "..\n.*12\n4\n...*\n..7";
+
+        assert_eq!(part2(&Schematic::parse(game)), 48);
+    }
+}
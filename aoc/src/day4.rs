@@ -0,0 +1,472 @@
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::io::{self, BufRead};
+use std::ops::ControlFlow;
+
+pub mod card;
+
+use crate::diagnostic::Diagnostic;
+use crate::input;
+use crate::solution::{Part, Solution, Solved, Solving};
+use card::{Card, ScratchCardError};
+
+// Error together with the line it was found on
+pub type LineError = (usize, ScratchCardError);
+
+impl From<LineError> for Diagnostic {
+    fn from((line, e): LineError) -> Self {
+        let diagnostic = match e {
+            ScratchCardError::MissingColon => Diagnostic::new("missing `:` after the card id"),
+            ScratchCardError::MissingBar => Diagnostic::new("missing `|` between numbers"),
+            ScratchCardError::BadNumber { text, column } => {
+                Diagnostic::new(format!("`{}` is not a number", text))
+                    .at_columns(column..column + text.chars().count())
+            }
+            ScratchCardError::DuplicateNumber { number, column } => {
+                Diagnostic::new(format!("number {} is repeated", number))
+                    .at_columns(column..column + number.to_string().len())
+            }
+            ScratchCardError::Overflow => Diagnostic::new("answer doesn't fit in 64 bits"),
+        };
+
+        diagnostic.at_line(line)
+    }
+}
+
+// What happened to one card during the cascade, for debugging
+#[derive(Debug, PartialEq)]
+pub struct CardTrace {
+    pub id: u32,
+    pub matches: usize,
+    pub copies: u64,
+    // ids of earlier cards that gave this card copies
+    pub from: Vec<u32>,
+}
+
+// `None` when there are so many matches that points don't fit in 64 bits
+fn points_for_scratchcard(card: &Card) -> Option<u64> {
+    match card.matches() {
+        0 => Some(0),
+        matches => 2u64.checked_pow(u32::try_from(matches - 1).ok()?),
+    }
+}
+
+fn add_points(sum: &mut u64, card: &Card) -> Result<(), ScratchCardError> {
+    *sum = points_for_scratchcard(card)
+        .and_then(|points| sum.checked_add(points))
+        .ok_or(ScratchCardError::Overflow)?;
+
+    Ok(())
+}
+
+pub fn parse_cards(cards: &str) -> Result<Vec<Card>, LineError> {
+    cards
+        .lines()
+        .enumerate()
+        .map(|(n, card)| card.parse().map_err(|e| (n + 1, e)))
+        .collect()
+}
+
+pub fn part1(cards: &[Card]) -> Result<u64, LineError> {
+    let mut sum = 0;
+
+    for (n, card) in cards.iter().enumerate() {
+        add_points(&mut sum, card).map_err(|e| (n + 1, e))?;
+    }
+
+    Ok(sum)
+}
+
+pub fn part2(cards: &[Card]) -> Result<u64, LineError> {
+    scratchcards_cascade(cards, None)
+}
+
+pub fn trace_scratchcards(cards: &[Card]) -> Result<(u64, Vec<CardTrace>), LineError> {
+    let mut traces = vec![];
+    let sum = scratchcards_cascade(cards, Some(&mut traces))?;
+
+    Ok((sum, traces))
+}
+
+// Copies won by cards that were already seen, only as many cards ahead as the most matches of one
+// card, so cards can be given to it one by one
+#[derive(Default)]
+struct Cascade {
+    sum: u64,
+    next_cards: VecDeque<(u64, Vec<u32>)>,
+}
+
+impl Cascade {
+    // Ids of givers are only remembered when traces are collected, won copies are never more than
+    // the sum of the cards before, so they can't overflow before the sum does
+    fn add(
+        &mut self,
+        card: &Card,
+        traces: Option<&mut Vec<CardTrace>>,
+    ) -> Result<(), ScratchCardError> {
+        let matches = card.matches();
+        let (amount_of_this_card, from) = self.count_next()?;
+
+        while self.next_cards.len() < matches {
+            self.next_cards.push_back(Default::default());
+        }
+
+        for (copies, from) in self.next_cards.iter_mut().take(matches) {
+            *copies += amount_of_this_card;
+
+            if traces.is_some() {
+                from.push(card.id);
+            }
+        }
+
+        if let Some(traces) = traces {
+            traces.push(CardTrace {
+                id: card.id,
+                matches,
+                copies: amount_of_this_card,
+                from,
+            });
+        }
+
+        Ok(())
+    }
+
+    // A card that couldn't be read still has its copies, it is counted as if it had no matches so
+    // the copies of the cards after it stay in place
+    fn skip(&mut self) -> Result<(), ScratchCardError> {
+        self.count_next().map(|_| ())
+    }
+
+    // Amount of the next card with the ids of the cards that gave copies of it
+    fn count_next(&mut self) -> Result<(u64, Vec<u32>), ScratchCardError> {
+        let (won_copies, from) = self.next_cards.pop_front().unwrap_or_default();
+        let amount = won_copies
+            .checked_add(1)
+            .ok_or(ScratchCardError::Overflow)?;
+
+        self.sum = self
+            .sum
+            .checked_add(amount)
+            .ok_or(ScratchCardError::Overflow)?;
+
+        Ok((amount, from))
+    }
+}
+
+fn scratchcards_cascade(
+    cards: &[Card],
+    mut traces: Option<&mut Vec<CardTrace>>,
+) -> Result<u64, LineError> {
+    let mut cascade = Cascade::default();
+
+    for (n, card) in cards.iter().enumerate() {
+        cascade
+            .add(card, traces.as_deref_mut())
+            .map_err(|e| (n + 1, e))?;
+    }
+
+    Ok(cascade.sum)
+}
+
+// Trace as a table, with the total in the last line
+pub fn trace_table(sum: u64, traces: &[CardTrace]) -> String {
+    let mut table = format!("{:>6} {:>8} {:>10}  From\n", "Card", "Matches", "Copies");
+
+    for trace in traces {
+        let from = trace
+            .from
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let row = format!(
+            "{:>6} {:>8} {:>10}  {}",
+            trace.id, trace.matches, trace.copies, from
+        );
+
+        writeln!(table, "{}", row.trim_end()).unwrap();
+    }
+
+    writeln!(table, "{}", sum).unwrap();
+    table
+}
+
+pub fn trace_json(sum: u64, traces: &[CardTrace]) -> String {
+    let cards = traces
+        .iter()
+        .map(|trace| {
+            format!(
+                "{{\"id\":{},\"matches\":{},\"copies\":{},\"from\":{:?}}}",
+                trace.id, trace.matches, trace.copies, trace.from
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!("{{\"total\":{},\"cards\":[{}]}}", sum, cards)
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u32 = 4;
+
+    type Input = Vec<Card>;
+    type Answer = u64;
+    type Error = LineError;
+
+    fn parse(&self, input: &str) -> Result<Vec<Card>, LineError> {
+        parse_cards(input)
+    }
+
+    fn part1(&self, cards: &Vec<Card>) -> Result<u64, LineError> {
+        part1(cards)
+    }
+
+    fn part2(&self, cards: &Vec<Card>) -> Result<u64, LineError> {
+        part2(cards)
+    }
+
+    // Copies won by a card still go to the cards that follow it among the ones that were read
+    fn solve_reader(
+        &self,
+        reader: &mut dyn BufRead,
+        part: Part,
+        lenient: bool,
+    ) -> Option<io::Result<Solved<u64, LineError>>> {
+        let mut solving = Solving::new(lenient);
+        let mut points = 0;
+        let mut cascade = Cascade::default();
+
+        let read = input::for_each_line(reader, |n, text| {
+            let card = match solving.parse(|| text.parse::<Card>()) {
+                Ok(card) => card,
+                Err(e) => {
+                    if part == Part::Two {
+                        if let Err(overflow) = cascade.skip() {
+                            return solving.stop((n, overflow), text);
+                        }
+                    }
+
+                    return solving.reject((n, e), text);
+                }
+            };
+            let added = match part {
+                Part::One => add_points(&mut points, &card),
+                Part::Two => cascade.add(&card, None),
+            };
+
+            // skipping the card wouldn't make the answer right
+            match added {
+                Ok(()) => ControlFlow::Continue(()),
+                Err(e) => solving.stop((n, e), text),
+            }
+        });
+
+        let answer = match part {
+            Part::One => points,
+            Part::Two => cascade.sum,
+        };
+
+        Some(read.map(|()| solving.finish(answer)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn test(card: &str, points: u64) {
+        assert_eq!(points_for_scratchcard(&card.parse().unwrap()), Some(points));
+    }
+
+    #[test]
+    fn test_game_1() {
+        test("Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53", 8);
+    }
+
+    #[test]
+    fn test_game_2() {
+        test("Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19", 2);
+    }
+
+    #[test]
+    fn test_game_3() {
+        test("Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1", 2);
+    }
+
+    #[test]
+    fn test_game_4() {
+        test("Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83", 1);
+    }
+
+    #[test]
+    fn test_game_5() {
+        test("Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36", 0);
+    }
+
+    #[test]
+    fn test_game_6() {
+        test("Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11", 0);
+    }
+
+    #[test]
+    fn test_points_from_scratchcards() {
+        let cards = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+        assert_eq!(part2(&parse_cards(cards).unwrap()), Ok(30));
+
+        let solved = Day4.solve_reader(&mut cards.as_bytes(), Part::Two, false);
+        let solved = solved.unwrap().unwrap();
+
+        assert_eq!(solved.answer, Some(30));
+        assert_eq!(solved.rejected, vec![]);
+    }
+
+    #[test]
+    fn test_parse_cards_reports_line() {
+        let cards = "Card 1: 41 48 | 83 86
+Card 2: 13 32 20 16 61";
+
+        assert_eq!(parse_cards(cards), Err((2, ScratchCardError::MissingBar)));
+    }
+
+    #[test]
+    fn test_trace_scratchcards() {
+        let cards = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1";
+
+        let (sum, traces) = trace_scratchcards(&parse_cards(cards).unwrap()).unwrap();
+
+        assert_eq!(sum, 7);
+        assert_eq!(
+            traces,
+            vec![
+                CardTrace {
+                    id: 1,
+                    matches: 4,
+                    copies: 1,
+                    from: vec![]
+                },
+                CardTrace {
+                    id: 2,
+                    matches: 2,
+                    copies: 2,
+                    from: vec![1]
+                },
+                CardTrace {
+                    id: 3,
+                    matches: 2,
+                    copies: 4,
+                    from: vec![1, 2]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lenient_skips_wrong_cards() {
+        let cards = "Card 1: 41 48 | 41 86
+Card 2: 13 32 20
+Card 3: 1 21 | 69 x";
+
+        assert_eq!(
+            Day4.solve_lenient(cards, Part::One),
+            Some((
+                Some(1),
+                vec![
+                    (2, ScratchCardError::MissingBar),
+                    (
+                        3,
+                        ScratchCardError::BadNumber {
+                            text: "x".to_string(),
+                            column: 19
+                        }
+                    ),
+                ]
+            ))
+        );
+    }
+
+    // A card with `matches` winning numbers that are all on the card
+    fn winning_card(id: usize, matches: u32) -> String {
+        let numbers = (1..=matches)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        format!("Card {id}: {numbers} | {numbers}")
+    }
+
+    fn winning_cards(amount: usize, matches: u32) -> String {
+        (1..=amount)
+            .map(|id| winning_card(id, matches))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_answers_past_32_bits() {
+        // Every card wins a copy of all following ones, so copies double each card
+        let cards = winning_cards(40, 64);
+
+        assert_eq!(Day4.solve(&cards, Part::Two), Ok((1 << 40) - 1));
+        assert_eq!(Day4.solve(&winning_card(1, 40), Part::One), Ok(1 << 39));
+    }
+
+    #[test]
+    fn test_overflow_stops_also_in_lenient_mode() {
+        let cards = winning_cards(70, 64);
+        let overflow = || (65, ScratchCardError::Overflow);
+
+        assert_eq!(Day4.solve(&cards, Part::Two), Err(overflow()));
+        assert_eq!(
+            Day4.solve_lenient(&cards, Part::Two),
+            Some((None, vec![overflow()]))
+        );
+        assert_eq!(
+            trace_scratchcards(&parse_cards(&cards).unwrap()),
+            Err(overflow())
+        );
+
+        let cards = format!("{}\n{}", winning_card(1, 64), winning_card(2, 64));
+
+        assert_eq!(
+            Day4.solve(&cards, Part::One),
+            Err((2, ScratchCardError::Overflow))
+        );
+        assert_eq!(
+            Day4.solve(&winning_card(1, 65), Part::One),
+            Err((1, ScratchCardError::Overflow))
+        );
+    }
+
+    #[test]
+    fn test_lenient_keeps_copies_after_wrong_cards() {
+        let cards = "Card 1: 41 48 83 | 41 48 5
+Card 2: 13 32 20
+Card 3: 1 21 | 69 82
+Card 4: 7 | 8";
+
+        // card 1 gives a copy to the wrong card 2 and to card 3, card 2 is still counted twice
+        assert_eq!(
+            Day4.solve_lenient(cards, Part::Two),
+            Some((Some(1 + 2 + 2 + 1), vec![(2, ScratchCardError::MissingBar)]))
+        );
+    }
+
+    #[test]
+    fn test_solution_reports_line() {
+        assert_eq!(
+            Day4.solve("Card 1: 41 48 | 83 86\nCard 2: 13 32 20", Part::One),
+            Err((2, ScratchCardError::MissingBar))
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use crate::diagnostic::columns_of;
+
+#[derive(Debug, PartialEq)]
+pub enum ScratchCardError {
+    MissingColon,
+    MissingBar,
+    // column of the first char of the text, counted from 1
+    BadNumber { text: String, column: usize },
+    DuplicateNumber { number: u32, column: usize },
+    // points or copies counted up to the card don't fit in 64 bits, the card itself is fine
+    Overflow,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Card {
+    pub id: u32,
+    pub winning: Vec<u32>,
+    pub have: Vec<u32>,
+}
+
+impl FromStr for Card {
+    type Err = ScratchCardError;
+
+    fn from_str(card: &str) -> Result<Self, Self::Err> {
+        let colon = card.find(':').ok_or(ScratchCardError::MissingColon)?;
+        let bar = colon
+            + 1
+            + card[colon + 1..]
+                .find('|')
+                .ok_or(ScratchCardError::MissingBar)?;
+
+        let header = &card[..colon];
+        let id = header
+            .strip_prefix("Card")
+            .ok_or_else(|| ScratchCardError::BadNumber {
+                text: header.to_string(),
+                column: 1,
+            })?;
+
+        let id = match numbers(card, id)?.as_slice() {
+            &[id] => id,
+            _ => {
+                return Err(ScratchCardError::BadNumber {
+                    text: id.trim().to_string(),
+                    column: columns_of(card, id.trim()).start,
+                })
+            }
+        };
+
+        Ok(Self {
+            id,
+            winning: numbers(card, &card[colon + 1..bar])?,
+            have: numbers(card, &card[bar + 1..])?,
+        })
+    }
+}
+
+// Parses whitespace separated numbers, `text` is a slice of the whole `card` line, so columns can
+// be counted from its start
+fn numbers(card: &str, text: &str) -> Result<Vec<u32>, ScratchCardError> {
+    let mut result = vec![];
+    let mut seen = HashSet::new();
+
+    for number in text.split_ascii_whitespace() {
+        let column = columns_of(card, number).start;
+
+        let number = number
+            .parse::<u32>()
+            .map_err(|_| ScratchCardError::BadNumber {
+                text: number.to_string(),
+                column,
+            })?;
+
+        if !seen.insert(number) {
+            return Err(ScratchCardError::DuplicateNumber { number, column });
+        }
+
+        result.push(number);
+    }
+
+    Ok(result)
+}
+
+impl Card {
+    // How many of the numbers we have are winning numbers
+    pub fn matches(&self) -> usize {
+        let mut winning = self.winning.clone();
+        winning.sort_unstable();
+
+        self.have
+            .iter()
+            .filter(|n| winning.binary_search(n).is_ok())
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_card() {
+        assert_eq!(
+            "Card  3:  1 21 | 69 1".parse::<Card>(),
+            Ok(Card {
+                id: 3,
+                winning: vec![1, 21],
+                have: vec![69, 1],
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_card_errors() {
+        assert_eq!(
+            "Card 1 41 48 | 83".parse::<Card>(),
+            Err(ScratchCardError::MissingColon)
+        );
+        assert_eq!(
+            "Card 1: 41 48 83".parse::<Card>(),
+            Err(ScratchCardError::MissingBar)
+        );
+        assert_eq!(
+            "Card 1: 41 4x | 83".parse::<Card>(),
+            Err(ScratchCardError::BadNumber {
+                text: "4x".to_string(),
+                column: 12
+            })
+        );
+        assert_eq!(
+            "Card x: 41 | 83".parse::<Card>(),
+            Err(ScratchCardError::BadNumber {
+                text: "x".to_string(),
+                column: 6
+            })
+        );
+        assert_eq!(
+            "Card 1: 41 | 8é  83".parse::<Card>(),
+            Err(ScratchCardError::BadNumber {
+                text: "8é".to_string(),
+                column: 14
+            })
+        );
+        assert_eq!(
+            "Card 1: é | 83  83".parse::<Card>(),
+            Err(ScratchCardError::BadNumber {
+                text: "é".to_string(),
+                column: 9
+            })
+        );
+        assert_eq!(
+            "Card 1: 41 | 83  83".parse::<Card>(),
+            Err(ScratchCardError::DuplicateNumber {
+                number: 83,
+                column: 18
+            })
+        );
+    }
+
+    #[test]
+    fn test_matches() {
+        let card = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"
+            .parse::<Card>()
+            .unwrap();
+
+        assert_eq!(card.matches(), 4);
+    }
+}
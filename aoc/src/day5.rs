@@ -0,0 +1,287 @@
+use std::ops::Range;
+use std::process;
+
+pub mod almanac;
+
+use crate::diagnostic::Diagnostic;
+use crate::input;
+use crate::solution::{self, Options, Part, Solution};
+use almanac::{Almanac, AlmanacError, Number};
+
+// Part 1 reads every seed on its own
+pub fn individual_seeds(almanac: &Almanac) -> Result<Vec<Range<Number>>, AlmanacError> {
+    almanac
+        .seeds
+        .iter()
+        .map(|&seed| seed_range(seed, 1))
+        .collect()
+}
+
+// Part 2 reads seeds as pairs of start and length of a range
+pub fn seed_ranges(almanac: &Almanac) -> Result<Vec<Range<Number>>, AlmanacError> {
+    almanac
+        .seeds
+        .chunks_exact(2)
+        .map(|pair| seed_range(pair[0], pair[1]))
+        .collect()
+}
+
+// Seeds are always on the first line
+fn seed_range(start: Number, length: Number) -> Result<Range<Number>, AlmanacError> {
+    match start.checked_add(length) {
+        Some(end) => Ok(start..end),
+        None => Err(AlmanacError::SeedOverflow {
+            seed: start,
+            line: 1,
+        }),
+    }
+}
+
+pub fn lowest_location(almanac: &Almanac, seeds: &[Range<Number>]) -> Result<Number, AlmanacError> {
+    almanac
+        .seed_to_location()?
+        .map_ranges(seeds)
+        .iter()
+        .map(|r| r.start)
+        .min()
+        .ok_or(AlmanacError::NoSeeds)
+}
+
+pub fn part1(almanac: &Almanac) -> Result<Number, AlmanacError> {
+    lowest_location(almanac, &individual_seeds(almanac)?)
+}
+
+pub fn part2(almanac: &Almanac) -> Result<Number, AlmanacError> {
+    lowest_location(almanac, &seed_ranges(almanac)?)
+}
+
+// First version of part 2 that moves every seed through the stages on its own, only kept to
+// compare it in benchmarks, it takes minutes for the real input
+pub fn part2_brute_force(almanac: &Almanac) -> Result<Number, AlmanacError> {
+    let stages = almanac.path("seed", "location")?;
+
+    seed_ranges(almanac)?
+        .into_iter()
+        .flatten()
+        .map(|seed| stages.iter().fold(seed, |x, stage| stage.map.apply(x)))
+        .min()
+        .ok_or(AlmanacError::NoSeeds)
+}
+
+// Strict solution rejects almanacs with overlapping or overflowing maps
+#[derive(Default)]
+pub struct Day5 {
+    pub strict: bool,
+}
+
+impl Solution for Day5 {
+    const DAY: u32 = 5;
+
+    type Input = Almanac;
+    type Answer = Number;
+    type Error = AlmanacError;
+
+    fn parse(&self, input: &str) -> Result<Almanac, AlmanacError> {
+        if self.strict {
+            Almanac::parse_strict(input)
+        } else {
+            Almanac::parse(input)
+        }
+    }
+
+    fn part1(&self, almanac: &Almanac) -> Result<Number, AlmanacError> {
+        part1(almanac)
+    }
+
+    fn part2(&self, almanac: &Almanac) -> Result<Number, AlmanacError> {
+        part2(almanac)
+    }
+
+    fn warnings(&self, almanac: &Almanac) -> Vec<Diagnostic> {
+        almanac.problems.iter().cloned().map(Into::into).collect()
+    }
+}
+
+// Range is given either as a single number or like `30..60`, empty and inverted ones are wrong
+pub fn parse_range(range: &str) -> Option<Range<Number>> {
+    match range.split_once("..") {
+        Some((start, end)) => {
+            let range = start.parse().ok()?..end.parse().ok()?;
+            (range.start < range.end).then_some(range)
+        }
+        None => {
+            let number = range.parse::<Number>().ok()?;
+            Some(number..number.checked_add(1)?)
+        }
+    }
+}
+
+fn print_trace(almanac: &Almanac, seeds: Range<Number>) -> Result<(), AlmanacError> {
+    let steps = almanac.trace(seeds.clone())?;
+
+    if seeds.end == seeds.start + 1 {
+        println!("seed {}", seeds.start);
+    } else {
+        println!("seeds {:?}", seeds);
+    }
+
+    for step in steps {
+        println!("  {}", step);
+    }
+
+    Ok(())
+}
+
+const USAGE: &str = "Usage: d5pN [--strict] [--report-normalization] [--format text|json] [paths]
+            [--print-map [from to] | --seeds-for locations | --trace [seeds]]";
+
+// Whole command line tool, parts differ only in how they read seeds, arguments after the first
+// mode flag belong to it and ones before it are paths of inputs
+pub fn run(part: Part) {
+    let (options, mut args) = Options::from_args();
+    let day = Day5 {
+        strict: options.strict,
+    };
+
+    let mode = match args.iter().position(|arg| arg.starts_with("--")) {
+        Some(i) => args.split_off(i),
+        None => vec![],
+    };
+    let paths = input::paths(args);
+
+    if mode.is_empty() {
+        return solution::run(&day, part, &options, &paths);
+    }
+
+    let mode = mode.iter().map(String::as_str).collect::<Vec<_>>();
+
+    let read_seeds = match part {
+        Part::One => individual_seeds,
+        Part::Two => seed_ranges,
+    };
+
+    input::for_each_input(&paths, options.report_normalization, |path, source| {
+        let almanac = day.parse(source)?;
+
+        for warning in day.warnings(&almanac) {
+            eprintln!("Warning: {}", warning);
+        }
+
+        if paths.len() > 1 {
+            println!("{}:", path);
+        }
+
+        let result = match mode.as_slice() {
+            ["--print-map"] => almanac.seed_to_location().map(|map| print!("{}", map)),
+            ["--print-map", from, to] => almanac.map_between(from, to).map(|map| print!("{}", map)),
+            ["--seeds-for", locations] => {
+                let Some(locations) = parse_range(locations) else {
+                    eprintln!("Wrong location range: {}", locations);
+                    process::exit(2);
+                };
+
+                almanac.seeds_for_locations(locations).map(|seeds| {
+                    for seeds in seeds {
+                        println!("{:?}", seeds);
+                    }
+                })
+            }
+            ["--trace"] => read_seeds(&almanac).and_then(|seeds| {
+                seeds
+                    .into_iter()
+                    .try_for_each(|seeds| print_trace(&almanac, seeds))
+            }),
+            ["--trace", seeds] => {
+                let Some(seeds) = parse_range(seeds) else {
+                    eprintln!("Wrong seed range: {}", seeds);
+                    process::exit(2);
+                };
+
+                print_trace(&almanac, seeds)
+            }
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(2);
+            }
+        };
+
+        Ok(result?)
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+    #[test]
+    fn test_part1() {
+        let almanac = EXAMPLE;
+
+        assert_eq!(part1(&Almanac::parse(almanac).unwrap()), Ok(35));
+    }
+
+    #[test]
+    fn test_part2() {
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+
+        assert_eq!(part2(&almanac), Ok(46));
+        assert_eq!(part2_brute_force(&almanac), Ok(46));
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("30..60"), Some(30..60));
+        assert_eq!(parse_range("7"), Some(7..8));
+        assert_eq!(parse_range("60..30"), None);
+        assert_eq!(parse_range("30..30"), None);
+        assert_eq!(parse_range("18446744073709551615"), None);
+    }
+
+    #[test]
+    fn test_seeds_that_dont_fit_in_a_number() {
+        let overflow = Err(AlmanacError::SeedOverflow {
+            seed: Number::MAX,
+            line: 1,
+        });
+
+        let almanac = Almanac::parse(&format!("seeds: {}", Number::MAX)).unwrap();
+        assert_eq!(part1(&almanac), overflow);
+
+        let almanac = Almanac::parse(&format!("seeds: {} 1", Number::MAX)).unwrap();
+        assert_eq!(part2(&almanac), overflow);
+    }
+}
@@ -0,0 +1,932 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::diagnostic::{columns_of, Diagnostic};
+
+pub type Number = u64;
+
+// Numbers from `start` to `end` are shifted, so `start` becomes `value`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matcher {
+    start: Number,
+    end: Number,
+    value: Number,
+}
+
+impl Matcher {
+    // Amount is cut, so neither source nor destination goes past `Number::MAX`
+    pub fn new(to: Number, from: Number, amount: Number) -> Self {
+        let amount = amount.min(Number::MAX - from).min(Number::MAX - to);
+
+        Self {
+            start: from,
+            end: from + amount,
+            value: to,
+        }
+    }
+
+    pub fn overflows(to: Number, from: Number, amount: Number) -> bool {
+        from.checked_add(amount).is_none() || to.checked_add(amount).is_none()
+    }
+
+    fn source(&self) -> Range<Number> {
+        self.start..self.end
+    }
+
+    fn destination(&self) -> Range<Number> {
+        self.value..self.value + (self.end - self.start)
+    }
+}
+
+// Where a matcher was written, so problems with it can point at its numbers
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatcherSpan {
+    pub line: usize,
+    pub to: Range<usize>,
+    pub from: Range<usize>,
+}
+
+// Matcher together with the place it was written on
+pub type LineMatcher = (MatcherSpan, Matcher);
+
+// Map made of sorted, non-overlapping matchers, numbers that are not covered by any matcher map to
+// themselves
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PiecewiseMap {
+    matchers: Vec<Matcher>,
+}
+
+impl PiecewiseMap {
+    // Where matchers overlap the first one wins, same as when they are checked one by one
+    pub fn new(matchers: Vec<Matcher>) -> Self {
+        let mut pieces: Vec<Matcher> = vec![];
+
+        for matcher in matchers {
+            let rest = uncovered(matcher.start..matcher.end, &pieces);
+
+            pieces.extend(rest.into_iter().map(|r| Matcher {
+                start: r.start,
+                end: r.end,
+                value: matcher.value + r.start - matcher.start,
+            }));
+        }
+
+        pieces.sort_by_key(|m| m.start);
+
+        Self { matchers: pieces }
+    }
+
+    pub fn apply(&self, x: Number) -> Number {
+        let i = self.matchers.partition_point(|m| m.end <= x);
+
+        match self.matchers.get(i) {
+            Some(m) if m.start <= x => m.value + x - m.start,
+            _ => x,
+        }
+    }
+
+    // Sub-ranges of `range` on which the map is a single shift, together with the mapped start of
+    // each of them, gaps between matchers are returned as well
+    fn split(&self, range: Range<Number>) -> Vec<(Range<Number>, Number)> {
+        let mut result = vec![];
+        let mut current = range.start;
+        let mut i = self.matchers.partition_point(|m| m.end <= range.start);
+
+        while current < range.end {
+            let (end, value) = match self.matchers.get(i) {
+                Some(m) if m.start <= current => {
+                    i += 1;
+                    (m.end.min(range.end), m.value + current - m.start)
+                }
+                Some(m) => (m.start.min(range.end), current),
+                None => (range.end, current),
+            };
+
+            result.push((current..end, value));
+            current = end;
+        }
+
+        result
+    }
+
+    pub fn map_ranges(&self, ranges: &[Range<Number>]) -> Vec<Range<Number>> {
+        ranges
+            .iter()
+            .flat_map(|range| self.split(range.clone()))
+            .map(|(range, value)| value..value + (range.end - range.start))
+            .collect()
+    }
+
+    // All numbers that are mapped into `range`, map doesn't have to be injective so they can come
+    // both from matchers and from gaps between them
+    pub fn preimage(&self, range: Range<Number>) -> Vec<Range<Number>> {
+        let mut result = vec![];
+
+        for (part, value) in self.split(0..Number::MAX) {
+            let start = range.start.max(value);
+            let end = range.end.min(value + (part.end - part.start));
+
+            if start < end {
+                result.push(part.start + (start - value)..part.start + (end - value));
+            }
+        }
+
+        merge_ranges(result)
+    }
+
+    // Map that works like applying `self` and then `next`
+    pub fn compose(&self, next: &PiecewiseMap) -> PiecewiseMap {
+        let mut matchers: Vec<Matcher> = vec![];
+
+        for (range, value) in self.split(0..Number::MAX) {
+            let image = value..value + (range.end - range.start);
+
+            for (part, part_value) in next.split(image) {
+                let start = range.start + (part.start - value);
+                let end = start + (part.end - part.start);
+
+                if start == part_value {
+                    continue;
+                }
+
+                // join with previous piece if it is the same shift
+                match matchers.last_mut() {
+                    Some(last)
+                        if last.end == start && last.value + (start - last.start) == part_value =>
+                    {
+                        last.end = end;
+                    }
+                    _ => matchers.push(Matcher {
+                        start,
+                        end,
+                        value: part_value,
+                    }),
+                }
+            }
+        }
+
+        Self { matchers }
+    }
+}
+
+// Sorts ranges and joins ones that overlap or touch
+fn merge_ranges(mut ranges: Vec<Range<Number>>) -> Vec<Range<Number>> {
+    ranges.sort_by_key(|r| r.start);
+
+    let mut result: Vec<Range<Number>> = vec![];
+
+    for range in ranges {
+        match result.last_mut() {
+            Some(last) if last.end >= range.start => last.end = last.end.max(range.end),
+            _ => result.push(range),
+        }
+    }
+
+    result
+}
+
+// Parts of `range` that are not covered by any of the pieces
+fn uncovered(range: Range<Number>, pieces: &[Matcher]) -> Vec<Range<Number>> {
+    pieces.iter().fold(vec![range], |rest, piece| {
+        rest.into_iter()
+            .flat_map(|r| {
+                [
+                    r.start..r.end.min(piece.start),
+                    r.start.max(piece.end)..r.end,
+                ]
+            })
+            .filter(|r| !r.is_empty())
+            .collect()
+    })
+}
+
+// Printed in the same `to from amount` format as maps in the almanac, so it can be read back
+impl fmt::Display for PiecewiseMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for m in self.matchers.iter() {
+            writeln!(f, "{} {} {}", m.value, m.start, m.end - m.start)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for PiecewiseMap {
+    type Err = ();
+
+    fn from_str(map: &str) -> Result<Self, Self::Err> {
+        map.lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| match parse_matcher(line).ok()? {
+                [(to, _), (from, _), (amount, _)] if Matcher::overflows(to, from, amount) => None,
+                [(to, _), (from, _), (amount, _)] => Some(Matcher::new(to, from, amount)),
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(Self::new)
+            .ok_or(())
+    }
+}
+
+// Parses `to from amount` line, every number comes with the part of the line it was read from,
+// on error that part is the first token which is wrong, or the end of the line if one is missing
+fn parse_matcher(line: &str) -> Result<[(Number, &str); 3], &str> {
+    let mut tokens = line.split_whitespace();
+    let mut numbers = [(0, ""); 3];
+
+    for number in numbers.iter_mut() {
+        let token = tokens.next().unwrap_or(&line[line.len()..]);
+        *number = (token.parse().map_err(|_| token)?, token);
+    }
+
+    match tokens.next() {
+        Some(extra) => Err(extra),
+        None => Ok(numbers),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AlmanacError {
+    // `columns` of the part of the line that couldn't be read
+    WrongFormat {
+        line: usize,
+        columns: Range<usize>,
+    },
+    DuplicateCategory {
+        category: String,
+        line: usize,
+        columns: Range<usize>,
+    },
+    UnknownCategory(String),
+    NoPath {
+        from: String,
+        to: String,
+    },
+    InvalidMap(MapProblem),
+    NoSeeds,
+    // range of seeds starting at `seed` ends past the largest `Number`
+    SeedOverflow {
+        seed: Number,
+        line: usize,
+    },
+}
+
+// Things that are allowed in lenient mode, but most likely mean that the almanac is broken
+#[derive(Debug, Clone, PartialEq)]
+// `columns` point at the number that causes the problem
+pub enum MapProblem {
+    // `line` is the later of two matchers, on lenient mode the earlier one wins
+    OverlappingSources {
+        line: usize,
+        columns: Range<usize>,
+        other_line: usize,
+    },
+    // two different numbers are mapped to the same one
+    OverlappingDestinations {
+        line: usize,
+        columns: Range<usize>,
+        other_line: usize,
+    },
+    // `from + amount` or `to + amount` doesn't fit in `Number`, on lenient mode amount is cut
+    Overflow {
+        line: usize,
+        columns: Range<usize>,
+    },
+}
+
+impl MapProblem {
+    pub fn line(&self) -> usize {
+        match self {
+            MapProblem::OverlappingSources { line, .. }
+            | MapProblem::OverlappingDestinations { line, .. }
+            | MapProblem::Overflow { line, .. } => *line,
+        }
+    }
+
+    pub fn columns(&self) -> Range<usize> {
+        match self {
+            MapProblem::OverlappingSources { columns, .. }
+            | MapProblem::OverlappingDestinations { columns, .. }
+            | MapProblem::Overflow { columns, .. } => columns.clone(),
+        }
+    }
+}
+
+impl From<AlmanacError> for Diagnostic {
+    fn from(e: AlmanacError) -> Self {
+        match e {
+            AlmanacError::WrongFormat { line, columns } => Diagnostic::new("wrong format")
+                .at_line(line)
+                .at_columns(columns),
+            AlmanacError::DuplicateCategory {
+                category,
+                line,
+                columns,
+            } => Diagnostic::new(format!("category `{}` is mapped twice", category))
+                .at_line(line)
+                .at_columns(columns),
+            AlmanacError::UnknownCategory(category) => {
+                Diagnostic::new(format!("unknown category `{}`", category))
+            }
+            AlmanacError::NoPath { from, to } => {
+                Diagnostic::new(format!("no maps lead from `{}` to `{}`", from, to))
+            }
+            AlmanacError::InvalidMap(problem) => problem.into(),
+            AlmanacError::NoSeeds => Diagnostic::new("no seeds"),
+            AlmanacError::SeedOverflow { seed, line } => {
+                Diagnostic::new(format!("seeds starting at {} don't fit in a number", seed))
+                    .at_line(line)
+            }
+        }
+    }
+}
+
+impl From<MapProblem> for Diagnostic {
+    fn from(problem: MapProblem) -> Self {
+        let message = match problem {
+            MapProblem::OverlappingSources { other_line, .. } => {
+                format!("sources overlap with the matcher on line {}", other_line)
+            }
+            MapProblem::OverlappingDestinations { other_line, .. } => {
+                format!(
+                    "destinations overlap with the matcher on line {}",
+                    other_line
+                )
+            }
+            MapProblem::Overflow { .. } => "numbers don't fit in 64 bits".to_string(),
+        };
+
+        Diagnostic::new(message)
+            .at_line(problem.line())
+            .at_columns(problem.columns())
+    }
+}
+
+// One `from-to-to map:` section of the almanac
+#[derive(Debug)]
+pub struct Stage {
+    pub to: String,
+    // matchers as they were written, with their line numbers
+    pub matchers: Vec<LineMatcher>,
+    pub map: PiecewiseMap,
+}
+
+// What one stage did with a part of traced range, `line` of the matcher that was used or `None`
+// when numbers were not covered by any matcher and stayed the same
+#[derive(Debug, PartialEq)]
+pub struct TraceStep {
+    pub stage: String,
+    pub from: Range<Number>,
+    pub to: Range<Number>,
+    pub line: Option<usize>,
+}
+
+// Single numbers are printed without range, so trace of one seed is easier to read
+impl fmt::Display for TraceStep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.from.end - self.from.start == 1 {
+            write!(
+                f,
+                "{}: {} -> {}",
+                self.stage, self.from.start, self.to.start
+            )?;
+        } else {
+            write!(f, "{}: {:?} -> {:?}", self.stage, self.from, self.to)?;
+        }
+
+        match self.line {
+            Some(line) => write!(f, " (line {})", line),
+            None => write!(f, " (identity)"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Almanac {
+    pub seeds: Vec<Number>,
+    // graph of categories, every category is converted to the next one by exactly one stage
+    pub stages: HashMap<String, Stage>,
+    // sorted by line
+    pub problems: Vec<MapProblem>,
+}
+
+// Parses header like `seed-to-soil map:`, on error returns the part of the line that is wrong
+fn parse_header(line: &str) -> Result<(&str, &str), &str> {
+    let name = line.strip_suffix(" map:").ok_or(line)?;
+    let (from, to) = name.split_once("-to-").ok_or(name)?;
+
+    if from.is_empty() || to.is_empty() {
+        return Err(name);
+    }
+
+    Ok((from, to))
+}
+
+// Parses `seeds: 79 14` line, on error returns the part of the line that is wrong
+fn parse_seeds(line: &str) -> Result<Vec<Number>, &str> {
+    line.strip_prefix("seeds:")
+        .ok_or(line)?
+        .split_whitespace()
+        .map(|s| s.parse().map_err(|_| s))
+        .collect()
+}
+
+fn wrong_format(n: usize, line: &str, part: &str) -> AlmanacError {
+    AlmanacError::WrongFormat {
+        line: n,
+        columns: columns_of(line, part),
+    }
+}
+
+impl Almanac {
+    pub fn parse(almanac: &str) -> Result<Self, AlmanacError> {
+        let mut lines = almanac.lines().enumerate().map(|(n, line)| (n + 1, line));
+        let (_, first) = lines.next().unwrap_or((1, ""));
+        let seeds = parse_seeds(first).map_err(|part| wrong_format(1, first, part))?;
+
+        let mut stages = HashMap::new();
+        let mut problems = vec![];
+        let mut current: Option<(&str, &str, Vec<LineMatcher>)> = None;
+
+        for (n, line) in lines {
+            match line {
+                l if l.ends_with("map:") => {
+                    let (from, to) = parse_header(l).map_err(|part| wrong_format(n, l, part))?;
+
+                    if stages.contains_key(from) || current.as_ref().is_some_and(|c| c.0 == from) {
+                        return Err(AlmanacError::DuplicateCategory {
+                            category: from.to_string(),
+                            line: n,
+                            columns: columns_of(l, from),
+                        });
+                    }
+
+                    // close previous stage and get ready for new
+                    if let Some((from, to, matchers)) = current.replace((from, to, vec![])) {
+                        stages.insert(from.to_string(), Stage::new(to, matchers));
+                    }
+                }
+                "" => {}
+                l => {
+                    let [(to, to_text), (from, from_text), (amount, amount_text)] =
+                        parse_matcher(l).map_err(|part| wrong_format(n, l, part))?;
+
+                    if Matcher::overflows(to, from, amount) {
+                        problems.push(MapProblem::Overflow {
+                            line: n,
+                            columns: columns_of(l, amount_text),
+                        });
+                    }
+
+                    let span = MatcherSpan {
+                        line: n,
+                        to: columns_of(l, to_text),
+                        from: columns_of(l, from_text),
+                    };
+
+                    current
+                        .as_mut()
+                        .ok_or_else(|| wrong_format(n, l, l))?
+                        .2
+                        .push((span, Matcher::new(to, from, amount)));
+                }
+            }
+        }
+
+        if let Some((from, to, matchers)) = current {
+            stages.insert(from.to_string(), Stage::new(to, matchers));
+        }
+
+        problems.extend(stages.values().flat_map(Stage::problems));
+        problems.sort_by_key(MapProblem::line);
+
+        Ok(Self {
+            seeds,
+            stages,
+            problems,
+        })
+    }
+
+    // Same as parse, but any problem with maps is an error
+    pub fn parse_strict(almanac: &str) -> Result<Self, AlmanacError> {
+        let mut almanac = Self::parse(almanac)?;
+
+        if !almanac.problems.is_empty() {
+            return Err(AlmanacError::InvalidMap(almanac.problems.remove(0)));
+        }
+
+        Ok(almanac)
+    }
+
+    fn has_category(&self, category: &str) -> bool {
+        self.stages.contains_key(category) || self.stages.values().any(|s| s.to == category)
+    }
+
+    // Stages that convert `from` to `to`, in order
+    pub fn path(&self, from: &str, to: &str) -> Result<Vec<&Stage>, AlmanacError> {
+        for category in [from, to] {
+            if !self.has_category(category) {
+                return Err(AlmanacError::UnknownCategory(category.to_string()));
+            }
+        }
+
+        let mut path = vec![];
+        let mut current = from;
+
+        while current != to {
+            // every category has at most one stage, so after visiting all of them we are in a cycle
+            match self.stages.get(current) {
+                Some(stage) if path.len() < self.stages.len() => {
+                    path.push(stage);
+                    current = &stage.to;
+                }
+                _ => {
+                    return Err(AlmanacError::NoPath {
+                        from: from.to_string(),
+                        to: to.to_string(),
+                    })
+                }
+            }
+        }
+
+        Ok(path)
+    }
+
+    // All stages between categories composed into one map
+    pub fn map_between(&self, from: &str, to: &str) -> Result<PiecewiseMap, AlmanacError> {
+        Ok(self
+            .path(from, to)?
+            .into_iter()
+            .fold(PiecewiseMap::default(), |map, stage| {
+                map.compose(&stage.map)
+            }))
+    }
+
+    pub fn seed_to_location(&self) -> Result<PiecewiseMap, AlmanacError> {
+        self.map_between("seed", "location")
+    }
+
+    // Ranges of `from` category that end up in `range` of `to` category, found by going through
+    // the stages backwards
+    pub fn preimage_between(
+        &self,
+        from: &str,
+        to: &str,
+        range: Range<Number>,
+    ) -> Result<Vec<Range<Number>>, AlmanacError> {
+        Ok(self
+            .path(from, to)?
+            .into_iter()
+            .rev()
+            .fold(vec![range], |ranges, stage| {
+                merge_ranges(
+                    ranges
+                        .into_iter()
+                        .flat_map(|range| stage.map.preimage(range))
+                        .collect(),
+                )
+            }))
+    }
+
+    // Every stage from seed to location, with the matcher that moved each part of `seeds`
+    pub fn trace(&self, seeds: Range<Number>) -> Result<Vec<TraceStep>, AlmanacError> {
+        let mut steps = vec![];
+        let mut ranges = vec![seeds];
+        let mut category = "seed";
+
+        for stage in self.path("seed", "location")? {
+            let name = format!("{}-to-{}", category, stage.to);
+            let mut next_ranges = vec![];
+
+            for range in ranges {
+                for (from, to, line) in stage.trace(range) {
+                    next_ranges.push(to.clone());
+                    steps.push(TraceStep {
+                        stage: name.clone(),
+                        from,
+                        to,
+                        line,
+                    });
+                }
+            }
+
+            ranges = next_ranges;
+            category = &stage.to;
+        }
+
+        Ok(steps)
+    }
+
+    pub fn seeds_for_locations(
+        &self,
+        locations: Range<Number>,
+    ) -> Result<Vec<Range<Number>>, AlmanacError> {
+        self.preimage_between("seed", "location", locations)
+    }
+}
+
+impl Stage {
+    fn new(to: &str, matchers: Vec<LineMatcher>) -> Self {
+        Self {
+            to: to.to_string(),
+            map: PiecewiseMap::new(matchers.iter().map(|(_, m)| m.clone()).collect()),
+            matchers,
+        }
+    }
+
+    // Parts of `range` with where they are mapped and by which line, matchers are checked in the
+    // order they were written, so the first one wins
+    fn trace(&self, range: Range<Number>) -> Vec<(Range<Number>, Range<Number>, Option<usize>)> {
+        let mut result = vec![];
+        let mut not_mapped = vec![range];
+
+        for (span, matcher) in self.matchers.iter() {
+            for part in not_mapped.iter() {
+                let start = part.start.max(matcher.start);
+                let end = part.end.min(matcher.end);
+
+                if start < end {
+                    let to = matcher.value + (start - matcher.start);
+                    result.push((start..end, to..to + (end - start), Some(span.line)));
+                }
+            }
+
+            not_mapped = not_mapped
+                .into_iter()
+                .flat_map(|r| uncovered(r, std::slice::from_ref(matcher)))
+                .collect();
+        }
+
+        result.extend(not_mapped.into_iter().map(|r| (r.clone(), r, None)));
+        result.sort_by_key(|(from, _, _)| from.start);
+        result
+    }
+
+    fn problems(&self) -> Vec<MapProblem> {
+        let overlaps = |a: Range<Number>, b: Range<Number>| a.start.max(b.start) < a.end.min(b.end);
+        let mut problems = vec![];
+
+        for (i, (span, matcher)) in self.matchers.iter().enumerate() {
+            for (other, other_matcher) in self.matchers[..i].iter() {
+                if overlaps(matcher.source(), other_matcher.source()) {
+                    problems.push(MapProblem::OverlappingSources {
+                        line: span.line,
+                        columns: span.from.clone(),
+                        other_line: other.line,
+                    });
+                }
+
+                if overlaps(matcher.destination(), other_matcher.destination()) {
+                    problems.push(MapProblem::OverlappingDestinations {
+                        line: span.line,
+                        columns: span.to.clone(),
+                        other_line: other.line,
+                    });
+                }
+            }
+        }
+
+        problems
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn map(matchers: &[(Number, Number, Number)]) -> PiecewiseMap {
+        PiecewiseMap::new(
+            matchers
+                .iter()
+                .map(|&(to, from, amount)| Matcher::new(to, from, amount))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_first_matcher_wins_on_overlap() {
+        let map = map(&[(100, 10, 10), (200, 15, 10)]);
+
+        assert_eq!(map.apply(9), 9);
+        assert_eq!(map.apply(15), 105);
+        assert_eq!(map.apply(20), 205);
+        assert_eq!(map.apply(25), 25);
+    }
+
+    #[test]
+    fn test_map_ranges_splits_at_matcher_boundaries() {
+        let map = map(&[(50, 98, 2), (52, 50, 48)]);
+
+        let mut ranges = map.map_ranges(&[40..60, 95..105]);
+        ranges.sort_by_key(|r| r.start);
+
+        assert_eq!(ranges, vec![40..50, 50..52, 52..62, 97..100, 100..105]);
+    }
+
+    #[test]
+    fn test_compose_matches_applying_one_by_one() {
+        let first = map(&[(50, 98, 2), (52, 50, 48)]);
+        let second = map(&[(0, 15, 37), (37, 52, 2), (39, 0, 15)]);
+
+        let composed = first.compose(&second);
+
+        for x in 0..120 {
+            assert_eq!(composed.apply(x), second.apply(first.apply(x)), "x: {x}");
+        }
+    }
+
+    #[test]
+    fn test_printed_map_can_be_read_back() {
+        let map = map(&[(50, 98, 2), (52, 50, 48)]);
+
+        assert_eq!(map.to_string().parse(), Ok(map));
+    }
+
+    #[test]
+    fn test_preimage_of_matchers_and_gaps() {
+        let map = map(&[(50, 98, 2), (52, 50, 48)]);
+
+        assert_eq!(map.preimage(49..53), vec![49..51, 98..100]);
+        assert_eq!(map.preimage(100..102), vec![100..102]);
+    }
+
+    #[test]
+    fn test_seeds_for_locations_inverts_stages() {
+        let almanac = Almanac::parse(
+            "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15",
+        )
+        .unwrap();
+
+        let map = almanac.map_between("seed", "fertilizer").unwrap();
+        let seeds = almanac
+            .preimage_between("seed", "fertilizer", 30..60)
+            .unwrap();
+
+        for seed in 0..200 {
+            let expected = (30..60).contains(&map.apply(seed));
+            assert_eq!(
+                seeds.iter().any(|r| r.contains(&seed)),
+                expected,
+                "seed: {seed}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_stages_can_come_in_any_order() {
+        let almanac = Almanac::parse(
+            "seeds: 1
+
+soil-to-water map:
+100 0 50
+
+seed-to-soil map:
+10 0 10",
+        )
+        .unwrap();
+
+        assert_eq!(almanac.map_between("seed", "water").unwrap().apply(5), 115);
+        assert_eq!(almanac.map_between("soil", "water").unwrap().apply(5), 105);
+        assert_eq!(almanac.map_between("soil", "soil").unwrap().apply(5), 5);
+        assert_eq!(
+            almanac.map_between("water", "seed").unwrap_err(),
+            AlmanacError::NoPath {
+                from: "water".to_string(),
+                to: "seed".to_string()
+            }
+        );
+        assert_eq!(
+            almanac.map_between("seed", "location").unwrap_err(),
+            AlmanacError::UnknownCategory("location".to_string())
+        );
+    }
+
+    #[test]
+    fn test_duplicate_and_malformed_headers() {
+        assert_eq!(
+            Almanac::parse("seeds: 1\n\nseed-to-soil map:\n1 2 3\n\nseed-to-water map:")
+                .unwrap_err(),
+            AlmanacError::DuplicateCategory {
+                category: "seed".to_string(),
+                line: 6,
+                columns: 1..5
+            }
+        );
+        assert_eq!(
+            Almanac::parse("seeds: 1\n\nseed-soil map:").unwrap_err(),
+            AlmanacError::WrongFormat {
+                line: 3,
+                columns: 1..10
+            }
+        );
+    }
+
+    #[test]
+    fn test_wrong_format_points_at_token() {
+        let wrong_format = |almanac: &str| match Almanac::parse(almanac).unwrap_err() {
+            AlmanacError::WrongFormat { line, columns } => (line, columns),
+            e => panic!("unexpected error: {:?}", e),
+        };
+
+        assert_eq!(wrong_format(""), (1, 1..1));
+        assert_eq!(wrong_format("seeds: 1 x2 3"), (1, 10..12));
+        assert_eq!(wrong_format("seed: 1"), (1, 1..8));
+        assert_eq!(wrong_format("seeds: 1\n\n-to-soil map:"), (3, 1..9));
+        assert_eq!(wrong_format("seeds: 1\n1 2 3"), (2, 1..6));
+        assert_eq!(wrong_format("seeds: 1\na-to-b map:\n1 -2 3"), (3, 3..5));
+        assert_eq!(wrong_format("seeds: 1\na-to-b map:\n1 2 3 4"), (3, 7..8));
+        assert_eq!(wrong_format("seeds: 1\na-to-b map:\n1 2"), (3, 4..4));
+    }
+
+    #[test]
+    fn test_problems_with_maps() {
+        let text = "seeds: 1
+
+seed-to-soil map:
+10 0 10
+30 5 10
+10 20 5
+0 18446744073709551610 10";
+
+        let almanac = Almanac::parse(text).unwrap();
+
+        assert_eq!(
+            almanac.problems,
+            vec![
+                MapProblem::OverlappingSources {
+                    line: 5,
+                    columns: 4..5,
+                    other_line: 4
+                },
+                MapProblem::OverlappingDestinations {
+                    line: 6,
+                    columns: 1..3,
+                    other_line: 4
+                },
+                MapProblem::Overflow {
+                    line: 7,
+                    columns: 24..26
+                },
+            ]
+        );
+        assert_eq!(almanac.map_between("seed", "soil").unwrap().apply(5), 15);
+        assert_eq!(
+            Almanac::parse_strict(text).unwrap_err(),
+            AlmanacError::InvalidMap(MapProblem::OverlappingSources {
+                line: 5,
+                columns: 4..5,
+                other_line: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let almanac = Almanac::parse(
+            "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-location map:
+0 15 37
+37 52 2
+39 0 15",
+        )
+        .unwrap();
+
+        let steps = almanac.trace(79..80).unwrap();
+
+        assert_eq!(
+            steps.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+            vec![
+                "seed-to-soil: 79 -> 81 (line 5)",
+                "soil-to-location: 81 -> 81 (identity)",
+            ]
+        );
+
+        let steps = almanac.trace(45..55).unwrap();
+
+        assert_eq!(
+            steps.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+            vec![
+                "seed-to-soil: 45..50 -> 45..50 (identity)",
+                "seed-to-soil: 50..55 -> 52..57 (line 5)",
+                "soil-to-location: 45..50 -> 30..35 (line 8)",
+                "soil-to-location: 52..54 -> 37..39 (line 9)",
+                "soil-to-location: 54..57 -> 54..57 (identity)",
+            ]
+        );
+    }
+}
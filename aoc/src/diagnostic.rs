@@ -0,0 +1,229 @@
+use std::fmt::{self, Write};
+use std::ops::Range;
+
+// Error pointing at the place in the input that caused it, every day's error converts to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    // only the runner knows where the input came from
+    pub file: Option<String>,
+    // counted from 1, `None` when the error is about the whole input
+    pub line: Option<usize>,
+    // counted from 1 with the end excluded, `None` when the whole line is wrong
+    pub columns: Option<Range<usize>>,
+    pub message: String,
+    // text of the line, kept when the input was read line by line and the source is gone
+    pub snippet: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            file: None,
+            line: None,
+            columns: None,
+            message: message.into(),
+            snippet: None,
+        }
+    }
+
+    pub fn at_line(self, line: usize) -> Self {
+        Self {
+            line: Some(line),
+            ..self
+        }
+    }
+
+    pub fn at_columns(self, columns: Range<usize>) -> Self {
+        Self {
+            columns: Some(columns),
+            ..self
+        }
+    }
+
+    pub fn in_file(self, file: impl Into<String>) -> Self {
+        Self {
+            file: Some(file.into()),
+            ..self
+        }
+    }
+
+    pub fn with_snippet(self, snippet: impl Into<String>) -> Self {
+        Self {
+            snippet: Some(snippet.into()),
+            ..self
+        }
+    }
+
+    // Message with the offending line of the source and a caret underline below it
+    pub fn render(&self, source: &str) -> String {
+        let mut result = format!("error: {}\n", self.message);
+
+        let location = match (&self.line, &self.columns) {
+            (Some(line), Some(columns)) => format!(":{}:{}", line, columns.start),
+            (Some(line), None) => format!(":{}", line),
+            _ => String::new(),
+        };
+        let file = self.file.as_deref().unwrap_or("<input>");
+
+        let Some(line) = self.line else {
+            if self.file.is_some() {
+                writeln!(result, " --> {}", file).unwrap();
+            }
+
+            return result;
+        };
+
+        let text = match &self.snippet {
+            Some(snippet) => Some(snippet.as_str()),
+            None => source.lines().nth(line - 1),
+        };
+
+        let Some(text) = text else {
+            writeln!(result, " --> {}{}", file, location).unwrap();
+            return result;
+        };
+
+        let columns = match &self.columns {
+            Some(columns) => columns.start..columns.end.max(columns.start + 1),
+            None => 1..text.chars().count().max(1) + 1,
+        };
+        let gutter = " ".repeat(line.to_string().len());
+
+        writeln!(result, "{}--> {}{}", gutter, file, location).unwrap();
+        writeln!(result, "{} |", gutter).unwrap();
+        writeln!(result, "{} | {}", line, text).unwrap();
+        writeln!(
+            result,
+            "{} | {}{}",
+            gutter,
+            " ".repeat(columns.start - 1),
+            "^".repeat(columns.end - columns.start)
+        )
+        .unwrap();
+
+        result
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+        }
+
+        match (&self.line, &self.columns) {
+            (Some(line), Some(columns)) => write!(f, "{}:{}: ", line, columns.start)?,
+            (Some(line), None) => write!(f, "{}: ", line)?,
+            _ if self.file.is_some() => write!(f, " ")?,
+            _ => {}
+        }
+
+        write!(f, "{}", self.message)
+    }
+}
+
+// Columns taken by `part`, which has to be a slice of `line`
+pub fn columns_of(line: &str, part: &str) -> Range<usize> {
+    let offset = part.as_ptr() as usize - line.as_ptr() as usize;
+    let start = line[..offset].chars().count() + 1;
+
+    start..start + part.chars().count()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let diagnostic = Diagnostic::new("color `purple` is not in the bag")
+            .at_line(2)
+            .at_columns(11..17)
+            .in_file("input.txt");
+
+        assert_eq!(
+            diagnostic.render("Game 1: 1 red\nGame 2: 1 purple\n"),
+            "error: color `purple` is not in the bag
+ --> input.txt:2:11
+  |
+2 | Game 2: 1 purple
+  |           ^^^^^^
+"
+        );
+    }
+
+    #[test]
+    fn test_render_whole_line() {
+        let source = "1\n".repeat(9) + "abc";
+        let diagnostic = Diagnostic::new("line has no digit").at_line(10);
+
+        assert_eq!(
+            diagnostic.render(&source),
+            "error: line has no digit
+  --> <input>:10
+   |
+10 | abc
+   | ^^^
+"
+        );
+    }
+
+    #[test]
+    fn test_render_without_line() {
+        assert_eq!(
+            Diagnostic::new("no seeds").render("seeds:"),
+            "error: no seeds\n"
+        );
+        assert_eq!(
+            Diagnostic::new("no seeds")
+                .in_file("a.txt")
+                .render("seeds:"),
+            "error: no seeds\n --> a.txt\n"
+        );
+    }
+
+    #[test]
+    fn test_render_without_source() {
+        assert_eq!(
+            Diagnostic::new("line has no digit").at_line(3).render(""),
+            "error: line has no digit\n --> <input>:3\n"
+        );
+    }
+
+    #[test]
+    fn test_render_snippet() {
+        let diagnostic = Diagnostic::new("malformed game")
+            .at_line(3)
+            .at_columns(9..12)
+            .with_snippet("Game 3: red");
+
+        assert_eq!(
+            diagnostic.render(""),
+            "error: malformed game
+ --> <input>:3:9
+  |
+3 | Game 3: red
+  |         ^^^
+"
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let diagnostic = Diagnostic::new("bad number").at_line(3).at_columns(5..7);
+
+        assert_eq!(diagnostic.to_string(), "3:5: bad number");
+        assert_eq!(
+            diagnostic.in_file("input.txt").to_string(),
+            "input.txt:3:5: bad number"
+        );
+    }
+
+    #[test]
+    fn test_columns_of() {
+        let line = "Game 1: 3 blue";
+
+        assert_eq!(columns_of(line, &line[8..]), 9..15);
+        assert_eq!(columns_of(line, &line[..0]), 1..1);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::diagnostic::Diagnostic;
+use crate::normalize::normalize;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+use std::ops::ControlFlow;
+use std::{env, fs, io, process};
+
+pub fn read_stdin() -> io::Result<String> {
+    let mut buffer = String::new();
+
+    io::stdin().read_to_string(&mut buffer)?;
+
+    Ok(buffer)
+}
+
+// Path that stands for stdin
+pub const STDIN: &str = "-";
+
+pub fn read_input(path: &str) -> io::Result<String> {
+    match path {
+        STDIN => read_stdin(),
+        path => fs::read_to_string(path),
+    }
+}
+
+// Input that is read as it's needed
+pub fn open_input(path: &str) -> io::Result<Box<dyn BufRead>> {
+    match path {
+        STDIN => Ok(Box::new(io::stdin().lock())),
+        path => Ok(Box::new(BufReader::new(File::open(path)?))),
+    }
+}
+
+// Calls `f` with every line and its number counted from 1 until it breaks, lines are read one by
+// one into the same buffer, so only the longest one is kept in memory
+pub fn for_each_line(
+    reader: &mut dyn BufRead,
+    mut f: impl FnMut(usize, &str) -> ControlFlow<()>,
+) -> io::Result<()> {
+    let mut buffer = String::new();
+    let mut number = 0;
+
+    loop {
+        buffer.clear();
+
+        if reader.read_line(&mut buffer)? == 0 {
+            return Ok(());
+        }
+
+        number += 1;
+
+        // same line endings as `str::lines`
+        let line = match buffer.strip_suffix('\n') {
+            Some(line) => line.strip_suffix('\r').unwrap_or(line),
+            None => &buffer,
+        };
+
+        if f(number, line).is_break() {
+            return Ok(());
+        }
+    }
+}
+
+// Arguments left after flags are paths of inputs, stdin is read when there are none
+pub fn paths(args: Vec<String>) -> Vec<String> {
+    if args.is_empty() {
+        vec![STDIN.to_string()]
+    } else {
+        args
+    }
+}
+
+// Arguments without the name of the program
+pub fn args() -> Vec<String> {
+    env::args().skip(1).collect()
+}
+
+// Removes every occurrence of the flag, returns whether there was any
+pub fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let len = args.len();
+    args.retain(|arg| arg != flag);
+
+    args.len() != len
+}
+
+pub fn exit_with_error(message: impl Display) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+// Calls `f` with the path and the normalized contents of every input, changes made by normalizing
+// are shown as warnings when they are reported, errors of `f` are shown with the part of the input
+// they point at, exits with 1 after going through all of them when any failed
+pub fn for_each_input(
+    paths: &[String],
+    report_normalization: bool,
+    mut f: impl FnMut(&str, &str) -> Result<(), Diagnostic>,
+) {
+    let mut failed = false;
+
+    for path in paths {
+        let in_file = |diagnostic: Diagnostic| match path.as_str() {
+            STDIN => diagnostic,
+            path => diagnostic.in_file(path),
+        };
+
+        let (source, result) = match read_input(path) {
+            Ok(source) => {
+                let (source, changes) = normalize(&source);
+
+                if report_normalization {
+                    for warning in changes.diagnostics() {
+                        eprintln!("Warning: {}", in_file(warning));
+                    }
+                }
+
+                let result = f(path, &source);
+                (source, result)
+            }
+            Err(e) => (String::new(), Err(Diagnostic::new(e.to_string()))),
+        };
+
+        if let Err(e) = result {
+            eprint!("{}", in_file(e).render(&source));
+            failed = true;
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_for_each_line() {
+        let input = "a\r\nb\n\nc\r";
+        let mut lines = vec![];
+
+        for_each_line(&mut input.as_bytes(), |number, line| {
+            lines.push((number, line.to_string()));
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            lines,
+            input
+                .lines()
+                .enumerate()
+                .map(|(n, line)| (n + 1, line.to_string()))
+                .collect::<Vec<_>>()
+        );
+
+        let mut count = 0;
+
+        for_each_line(&mut input.as_bytes(), |_, _| {
+            count += 1;
+            ControlFlow::Break(())
+        })
+        .unwrap();
+
+        assert_eq!(count, 1);
+    }
+}
@@ -0,0 +1,16 @@
+// Solvers for every day, each day module implements `Solution`, binaries in `dNpM`
+// directories only read the input and print answers, `aoc` binary runs any of them
+pub mod alloc;
+pub mod bench;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod diagnostic;
+pub mod input;
+pub mod normalize;
+pub mod pattern_matcher;
+pub mod report;
+pub mod runner;
+pub mod solution;
@@ -0,0 +1,390 @@
+use aoc::alloc::Allocations;
+use aoc::bench::{self, Baseline, Measurement, Stats};
+use aoc::diagnostic::Diagnostic;
+use aoc::input;
+use aoc::report::{Format, Report};
+use aoc::runner::{self, Verdict};
+use aoc::solution::{self, Options};
+use std::fs;
+use std::process;
+use std::time::Duration;
+
+const USAGE: &str =
+    "Usage: aoc run <day> <part> [paths] [--lenient] [--strict] [--report-normalization]
+                                        [--format text|json]
+       aoc run all [--lenient] [--strict] [--report-normalization] [--format text|json]
+       aoc verify
+       aoc record <day> <part>
+       aoc bench [--runs N] [--save path] [--baseline path] [--variants]";
+
+const DEFAULT_RUNS: usize = 100;
+
+#[cfg(feature = "count-allocations")]
+#[global_allocator]
+static ALLOCATOR: aoc::alloc::CountingAllocator = aoc::alloc::CountingAllocator::new();
+
+#[cfg(feature = "count-allocations")]
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, Option<Allocations>) {
+    ALLOCATOR.reset();
+    let result = f();
+
+    (result, Some(ALLOCATOR.allocations()))
+}
+
+#[cfg(not(feature = "count-allocations"))]
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, Option<Allocations>) {
+    (f(), None)
+}
+
+struct Row {
+    report: Report,
+    // input the answer was found for, kept to show where errors are
+    source: String,
+    allocations: Option<Allocations>,
+    // what `verify` thinks about the answer, empty for `run`
+    status: String,
+}
+
+// Days that can't skip wrong lines stop on the first one also in lenient mode
+fn run(day: u32, part: u32, path: &str, options: &Options) -> Row {
+    let Some(solver) = runner::solver(day, part, options.strict) else {
+        input::exit_with_error(format!("No solver for day {} part {}", day, part));
+    };
+
+    let reported = input::open_input(path)
+        .map(|mut reader| count_allocations(|| solver.report(&mut reader, options)));
+
+    let ((report, source), allocations) = match reported {
+        Ok((Ok(reported), allocations)) => (reported, allocations),
+        Ok((Err(e), _)) | Err(e) => {
+            let report = Report::failed(day, part, Diagnostic::new(e.to_string()));
+            ((report, String::new()), None)
+        }
+    };
+
+    let status = match report.errors.len() {
+        n if n > 0 && report.answer.is_some() => format!("rejected lines: {}", n),
+        _ => String::new(),
+    };
+
+    let report = match path {
+        input::STDIN => report,
+        path => report.in_file(path),
+    };
+
+    Row {
+        report,
+        source,
+        allocations,
+        status,
+    }
+}
+
+// Allocation columns are only shown when they were counted
+fn print_table(rows: &[Row]) {
+    let mut header = format!("{:>3} {:>4} {:>12} {:>12}", "Day", "Part", "Answer", "Time");
+
+    if cfg!(feature = "count-allocations") {
+        header += &format!(" {:>9} {:>12} {:>12}", "Allocs", "Bytes", "Peak");
+    }
+
+    println!("{}", header);
+
+    for row in rows {
+        let report = &row.report;
+        let answer = match (&report.answer, report.errors.first()) {
+            (Some(answer), _) => answer.clone(),
+            (None, Some(e)) => format!("Error: {}", e),
+            (None, None) => "Error".to_string(),
+        };
+
+        let mut line = format!(
+            "{:>3} {:>4} {:>12} {:>12}",
+            report.day,
+            report.part,
+            answer,
+            format!("{:.2?}", report.time())
+        );
+
+        if let Some(allocations) = row.allocations {
+            line += &format!(
+                " {:>9} {:>12} {:>12}",
+                allocations.count, allocations.bytes, allocations.peak
+            );
+        }
+
+        println!("{}", format!("{}  {}", line, row.status).trim_end());
+    }
+
+    let total = rows.iter().map(|row| row.report.time()).sum::<Duration>();
+    println!("{:>34}", format!("{:.2?}", total));
+}
+
+fn stats(stats: &Stats) -> String {
+    format!(
+        "{:>10} {:>10} {:>10}",
+        format!("{:.2?}", stats.min),
+        format!("{:.2?}", stats.median),
+        format!("{:.2?}", stats.mean)
+    )
+}
+
+fn measure(
+    solver: &dyn runner::Solver,
+    path: &str,
+    runs: usize,
+) -> Result<Measurement, Diagnostic> {
+    fs::read_to_string(path)
+        .map_err(|e| Diagnostic::new(e.to_string()))
+        .and_then(|input| solver.bench(&input, runs))
+        .map_err(|e| e.in_file(path))
+}
+
+// Earlier solutions of the part are measured after it, when `variants` are asked for, and their
+// medians are compared with the current solution, some of them take minutes, so few runs are
+// enough
+fn bench_variants(day: u32, part: u32, current: &Measurement, runs: usize) {
+    let path = runner::default_input(day, part);
+
+    for variant in runner::variants() {
+        if (variant.day, variant.part) != (day, part) {
+            continue;
+        }
+
+        match measure(variant.solver.as_ref(), &path, runs) {
+            Ok(measurement) => println!(
+                "{:>8}  {}  {}  {}: parse {}, solve {}",
+                "",
+                stats(&measurement.parse),
+                stats(&measurement.solve),
+                variant.name,
+                bench::change(current.parse.median, measurement.parse.median),
+                bench::change(current.solve.median, measurement.solve.median)
+            ),
+            Err(e) => println!("{:>8}  {}: Error: {}", "", variant.name, e),
+        }
+    }
+}
+
+// Medians are compared with the baseline, if one is given
+fn bench(runs: usize, save: Option<&str>, baseline: Option<&Baseline>, variants: bool) {
+    let mut measurements = vec![];
+
+    println!(
+        "{:>3} {:>4}  {:>10} {:>10} {:>10}  {:>10} {:>10} {:>10}",
+        "Day", "Part", "Parse min", "median", "mean", "Solve min", "median", "mean"
+    );
+
+    for (day, part) in runner::all() {
+        let path = runner::default_input(day, part);
+        let solver = runner::solver(day, part, false).unwrap();
+
+        let measurement = match measure(solver.as_ref(), &path, runs) {
+            Ok(measurement) => measurement,
+            Err(e) => {
+                println!("{:>3} {:>4}  Error: {}", day, part, e);
+                continue;
+            }
+        };
+
+        let compared = match baseline.and_then(|baseline| baseline.get(&(day, part))) {
+            Some((parse, solve)) => format!(
+                "  parse {}, solve {}",
+                bench::change(*parse, measurement.parse.median),
+                bench::change(*solve, measurement.solve.median)
+            ),
+            None => String::new(),
+        };
+
+        println!(
+            "{:>3} {:>4}  {}  {}{}",
+            day,
+            part,
+            stats(&measurement.parse),
+            stats(&measurement.solve),
+            compared
+        );
+
+        if variants {
+            bench_variants(day, part, &measurement, runs);
+        }
+
+        measurements.push((day, part, measurement));
+    }
+
+    if let Some(path) = save {
+        if let Err(e) = fs::write(path, bench::format_baseline(&measurements)) {
+            input::exit_with_error(format!("Can't write {}: {}", path, e));
+        }
+    }
+}
+
+fn parse_day_and_part(day: &str, part: &str) -> (u32, u32) {
+    match (day.parse(), part.parse()) {
+        (Ok(day), Ok(part)) => (day, part),
+        _ => input::exit_with_error(USAGE),
+    }
+}
+
+fn main() {
+    let (options, args) = Options::from_args();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    match args.as_slice() {
+        ["run", "all"] => {
+            let rows = runner::all()
+                .map(|(day, part)| {
+                    let path = runner::default_input(day, part);
+                    run(day, part, &path, &options)
+                })
+                .collect::<Vec<_>>();
+
+            match options.format {
+                Format::Json => {
+                    for row in rows.iter() {
+                        println!("{}", row.report.to_json());
+                    }
+                }
+                Format::Text => {
+                    print_table(&rows);
+
+                    for row in rows.iter().filter(|row| row.report.answer.is_some()) {
+                        solution::report_rejected(row.report.errors.iter().cloned(), &row.source);
+                    }
+                }
+            }
+
+            if rows.iter().any(|row| row.report.answer.is_none()) {
+                process::exit(1);
+            }
+        }
+        ["run", day, part, paths @ ..] => {
+            let (day, part) = parse_day_and_part(day, part);
+
+            let paths = match paths {
+                [] => vec![runner::default_input(day, part)],
+                paths => paths.iter().map(|path| path.to_string()).collect(),
+            };
+
+            let mut failed = false;
+
+            for path in paths.iter() {
+                let row = run(day, part, path, &options);
+
+                solution::print_report(&row.report, &row.source, options.format, paths.len() > 1);
+                failed |= row.report.answer.is_none();
+            }
+
+            if failed {
+                process::exit(1);
+            }
+        }
+        ["verify"] => {
+            let mut failed = false;
+
+            let rows = runner::all()
+                .map(|(day, part)| {
+                    let mut row = run(
+                        day,
+                        part,
+                        &runner::default_input(day, part),
+                        &Options::default(),
+                    );
+
+                    if let Some(answer) = &row.report.answer {
+                        let expected = runner::recorded_answer(day, part);
+
+                        row.status = match runner::verdict(answer, expected.as_deref()) {
+                            Verdict::Correct => "ok".to_string(),
+                            Verdict::Wrong { expected } => {
+                                failed = true;
+                                format!("WRONG, expected {}", expected)
+                            }
+                            Verdict::NotRecorded => "not recorded".to_string(),
+                        };
+                    } else {
+                        failed = true;
+                    }
+
+                    row
+                })
+                .collect::<Vec<_>>();
+
+            print_table(&rows);
+
+            if failed {
+                process::exit(1);
+            }
+        }
+        ["record", day, part] => {
+            let (day, part) = parse_day_and_part(day, part);
+
+            let row = run(
+                day,
+                part,
+                &runner::default_input(day, part),
+                &Options::default(),
+            );
+            let Some(answer) = row.report.answer else {
+                solution::print_report(&row.report, &row.source, Format::Text, false);
+                process::exit(1);
+            };
+
+            if let Err(e) = runner::record_answer(day, part, &answer) {
+                input::exit_with_error(format!(
+                    "Can't write {}: {}",
+                    runner::answer_path(day, part),
+                    e
+                ));
+            }
+
+            println!("Recorded answer {} for day {} part {}", answer, day, part);
+        }
+        ["bench", flags @ ..] => {
+            let mut runs = DEFAULT_RUNS;
+            let mut save = None;
+            let mut baseline = None;
+            let mut variants = false;
+            let mut flags = flags;
+
+            while !flags.is_empty() {
+                let rest = match flags {
+                    ["--variants", rest @ ..] => {
+                        variants = true;
+                        rest
+                    }
+                    ["--runs", n, rest @ ..] => {
+                        runs = n.parse().unwrap_or_else(|_| input::exit_with_error(USAGE));
+                        rest
+                    }
+                    ["--save", path, rest @ ..] => {
+                        save = Some(*path);
+                        rest
+                    }
+                    ["--baseline", path, rest @ ..] => {
+                        let text = fs::read_to_string(path).unwrap_or_else(|e| {
+                            input::exit_with_error(format!("Can't read {}: {}", path, e))
+                        });
+
+                        baseline = Some(bench::parse_baseline(&text).unwrap_or_else(|line| {
+                            input::exit_with_error(format!(
+                                "Wrong baseline format on line {}",
+                                line
+                            ))
+                        }));
+                        rest
+                    }
+                    _ => input::exit_with_error(USAGE),
+                };
+
+                flags = rest;
+            }
+
+            bench(runs, save, baseline.as_ref(), variants);
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use crate::diagnostic::Diagnostic;
+use std::io::{self, BufRead, Read};
+
+const BOM: &[u8] = b"\xef\xbb\xbf";
+
+// Lines that got the same change, only counted so long inputs don't need more memory
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Lines {
+    pub count: usize,
+    // counted from 1
+    pub first: usize,
+}
+
+impl Lines {
+    fn add(&mut self, line: usize) {
+        if self.count == 0 {
+            self.first = line;
+        }
+
+        self.count += 1;
+    }
+}
+
+// Everything normalization changed in the input
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Changes {
+    pub bom: bool,
+    pub crlf: Lines,
+    pub trailing_whitespace: Lines,
+    // only blank lines at the end are removed, the ones between sections are kept
+    pub blank_lines: Lines,
+}
+
+impl Changes {
+    // One diagnostic for every kind of change, pointing at the first line that got it
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+
+        if self.bom {
+            diagnostics.push(Diagnostic::new("byte order mark removed").at_line(1));
+        }
+
+        let mut add = |lines: Lines, message: String| {
+            if lines.count > 0 {
+                diagnostics.push(Diagnostic::new(message).at_line(lines.first));
+            }
+        };
+
+        add(
+            self.crlf,
+            format!(
+                "`\\r\\n` replaced with `\\n` on {}",
+                count(self.crlf, "line")
+            ),
+        );
+        add(
+            self.trailing_whitespace,
+            format!(
+                "trailing whitespace removed from {}",
+                count(self.trailing_whitespace, "line")
+            ),
+        );
+        add(
+            self.blank_lines,
+            format!(
+                "{} removed from the end",
+                count(self.blank_lines, "blank line")
+            ),
+        );
+
+        diagnostics
+    }
+}
+
+fn count(lines: Lines, what: &str) -> String {
+    match lines.count {
+        1 => format!("1 {}", what),
+        count => format!("{} {}s", count, what),
+    }
+}
+
+// Fixes inputs saved on Windows or copied from a browser while they are read, one line at a time:
+// byte order mark is removed, every line ends with `\n` without whitespace before it and blank
+// lines at the end are dropped, line numbers stay the same
+pub struct Normalizing<R> {
+    inner: R,
+    raw: Vec<u8>,
+    // normalized lines that are ready to be read
+    buffer: Vec<u8>,
+    position: usize,
+    line: usize,
+    // blank lines are only kept once something follows them
+    blank: usize,
+    changes: Changes,
+}
+
+impl<R: BufRead> Normalizing<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            raw: vec![],
+            buffer: vec![],
+            position: 0,
+            line: 0,
+            blank: 0,
+            changes: Changes::default(),
+        }
+    }
+
+    // Complete only after the whole input was read
+    pub fn changes(&self) -> &Changes {
+        &self.changes
+    }
+
+    // Leaves the buffer empty at the end of the input
+    fn next_lines(&mut self) -> io::Result<()> {
+        self.buffer.clear();
+        self.position = 0;
+
+        loop {
+            self.raw.clear();
+
+            if self.inner.read_until(b'\n', &mut self.raw)? == 0 {
+                if self.blank > 0 {
+                    self.changes.blank_lines = Lines {
+                        count: self.blank,
+                        first: self.line - self.blank + 1,
+                    };
+                    self.blank = 0;
+                }
+
+                return Ok(());
+            }
+
+            self.line += 1;
+
+            let mut line = self.raw.as_slice();
+
+            if self.line == 1 && line.starts_with(BOM) {
+                line = &line[BOM.len()..];
+                self.changes.bom = true;
+            }
+
+            if let Some(without_newline) = line.strip_suffix(b"\n") {
+                line = without_newline;
+
+                if let Some(without_return) = line.strip_suffix(b"\r") {
+                    line = without_return;
+                    self.changes.crlf.add(self.line);
+                }
+            }
+
+            let trimmed = line.trim_ascii_end();
+
+            if trimmed.len() != line.len() {
+                self.changes.trailing_whitespace.add(self.line);
+            }
+
+            if trimmed.is_empty() {
+                self.blank += 1;
+                continue;
+            }
+
+            self.buffer.resize(self.blank, b'\n');
+            self.buffer.extend_from_slice(trimmed);
+            self.buffer.push(b'\n');
+            self.blank = 0;
+
+            return Ok(());
+        }
+    }
+}
+
+impl<R: BufRead> Read for Normalizing<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let amount = available.len().min(buf.len());
+
+        buf[..amount].copy_from_slice(&available[..amount]);
+        self.consume(amount);
+
+        Ok(amount)
+    }
+}
+
+impl<R: BufRead> BufRead for Normalizing<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.position == self.buffer.len() {
+            self.next_lines()?;
+        }
+
+        Ok(&self.buffer[self.position..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position += amount;
+    }
+}
+
+pub fn normalize(input: &str) -> (String, Changes) {
+    let mut reader = Normalizing::new(input.as_bytes());
+    let mut normalized = String::new();
+
+    reader
+        .read_to_string(&mut normalized)
+        .expect("reading from memory doesn't fail");
+
+    (normalized, reader.changes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let input = "\u{feff}seeds: 79 14\r\n\r\nseed-to-soil map: \r\n50 98 2\t\n\n  \n";
+
+        assert_eq!(
+            normalize(input),
+            (
+                "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n".to_string(),
+                Changes {
+                    bom: true,
+                    crlf: Lines { count: 3, first: 1 },
+                    trailing_whitespace: Lines { count: 3, first: 3 },
+                    blank_lines: Lines { count: 2, first: 5 },
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_normalize_keeps_clean_input() {
+        let input = "1abc2\npqr3stu8vwx\n";
+
+        assert_eq!(normalize(input), (input.to_string(), Changes::default()));
+        assert_eq!(normalize("a\nb").0, "a\nb\n");
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let (_, changes) = normalize("\u{feff}a\r\nb \n\n");
+
+        assert_eq!(
+            changes
+                .diagnostics()
+                .iter()
+                .map(|diagnostic| diagnostic.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "1: byte order mark removed",
+                "1: `\\r\\n` replaced with `\\n` on 1 line",
+                "2: trailing whitespace removed from 1 line",
+                "3: 1 blank line removed from the end",
+            ]
+        );
+    }
+}
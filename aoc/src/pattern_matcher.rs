@@ -0,0 +1,432 @@
+use std::collections::{HashMap, VecDeque};
+
+// Structure to match a pattern in a text
+#[derive(Debug, Clone)]
+pub struct PatternMatcher {
+    pattern: String,
+    value: u32,
+    current_matched_chars: usize,
+    pi: Vec<usize>,
+}
+
+impl PatternMatcher {
+    pub fn new(pattern: &str, value: u32) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            value,
+            current_matched_chars: 0,
+            pi: pi(pattern.chars().collect::<Vec<_>>()),
+        }
+    }
+
+    // Based on KMP algorithm, becouse after reading 'nin' and when next char is 'i' we can't just
+    // start from the beginning, we need to go back to 'ni', returns bool if match is found
+    pub fn matches(&mut self, c: char) -> bool {
+        while self.current_matched_chars > 0
+            && self.pattern.chars().nth(self.current_matched_chars) != Some(c)
+        {
+            self.current_matched_chars = self.pi[self.current_matched_chars - 1];
+        }
+
+        if self.pattern.chars().nth(self.current_matched_chars) == Some(c) {
+            self.current_matched_chars += 1;
+        }
+
+        if self.current_matched_chars == self.pattern.len() {
+            self.current_matched_chars = self.pi[self.current_matched_chars - 1];
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    // Reverse pattern, becouse we need to match from the end of the line, pi table has to be
+    // calculated again, reversed pi table is not prefix function of reversed pattern
+    pub fn reverse(&self) -> Self {
+        let pattern = self.pattern.chars().rev().collect::<String>();
+        Self::new(&pattern, self.value)
+    }
+
+    pub fn reset(&mut self) {
+        self.current_matched_chars = 0;
+    }
+}
+
+// Structure to match many patterns at once in a text, based on Aho-Corasick algorithm: patterns
+// are stored in a trie and every node has a failure link to the longest proper suffix of its word
+// that is also in the trie, so after a mismatch we don't need to start from the beginning.
+// Reports the leftmost match, the one that starts first, when two start on the same char the one
+// that ends first wins
+#[derive(Debug, Clone)]
+pub struct MultiPatternMatcher {
+    patterns: Vec<(String, u32)>,
+    nodes: Vec<Node>,
+    current_node: usize,
+    // chars read since the last reset
+    position: usize,
+    // start and value of the leftmost match found so far, kept until no pattern that started
+    // before it can still end
+    found: Option<(usize, u32)>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    children: HashMap<char, usize>,
+    fail: usize,
+    // length of the word of this node
+    depth: usize,
+    // length and value of the longest pattern that ends in this node, either own or from
+    // failure links
+    output: Option<(usize, u32)>,
+}
+
+impl MultiPatternMatcher {
+    pub fn new(patterns: &[(&str, u32)]) -> Self {
+        let patterns = patterns
+            .iter()
+            .map(|(pattern, value)| (pattern.to_string(), *value))
+            .collect();
+
+        Self::from_patterns(patterns)
+    }
+
+    fn from_patterns(patterns: Vec<(String, u32)>) -> Self {
+        let mut nodes = vec![Node::default()];
+
+        for (pattern, value) in patterns.iter() {
+            let mut current = 0;
+
+            for c in pattern.chars() {
+                current = match nodes[current].children.get(&c) {
+                    Some(&next) => next,
+                    None => {
+                        nodes.push(Node {
+                            depth: nodes[current].depth + 1,
+                            ..Node::default()
+                        });
+                        let next = nodes.len() - 1;
+                        nodes[current].children.insert(c, next);
+                        next
+                    }
+                };
+            }
+
+            // the same pattern given twice keeps its first value
+            let depth = nodes[current].depth;
+            nodes[current].output.get_or_insert((depth, *value));
+        }
+
+        // BFS, so failure links of shorter words are ready before they are needed
+        let mut queue = nodes[0].children.values().copied().collect::<VecDeque<_>>();
+
+        while let Some(node) = queue.pop_front() {
+            let children = nodes[node]
+                .children
+                .iter()
+                .map(|(&c, &child)| (c, child))
+                .collect::<Vec<_>>();
+
+            for (c, child) in children {
+                let mut fail = nodes[node].fail;
+
+                while fail > 0 && !nodes[fail].children.contains_key(&c) {
+                    fail = nodes[fail].fail;
+                }
+
+                let fail = nodes[fail].children.get(&c).copied().unwrap_or(0);
+
+                nodes[child].fail = fail;
+                nodes[child].output = nodes[child].output.or(nodes[fail].output);
+
+                queue.push_back(child);
+            }
+        }
+
+        Self {
+            patterns,
+            nodes,
+            current_node: 0,
+            position: 0,
+            found: None,
+        }
+    }
+
+    // Returns value of the leftmost match once it's sure that no pattern starting before it can
+    // still end, which can be some chars after the match ended, call `finish` after the last char
+    pub fn matches(&mut self, c: char) -> Option<u32> {
+        while self.current_node > 0 && !self.nodes[self.current_node].children.contains_key(&c) {
+            self.current_node = self.nodes[self.current_node].fail;
+        }
+
+        self.current_node = self.nodes[self.current_node]
+            .children
+            .get(&c)
+            .copied()
+            .unwrap_or(0);
+        self.position += 1;
+
+        let node = &self.nodes[self.current_node];
+
+        if let Some((length, value)) = node.output {
+            let start = self.position - length;
+
+            if self.found.is_none_or(|(found, _)| start < found) {
+                self.found = Some((start, value));
+            }
+        }
+
+        // every pattern that is still being read started within the word of the current node
+        match self.found {
+            Some((start, value)) if self.position - node.depth >= start => {
+                self.found = None;
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    // Match that was still waiting for longer patterns when the text ended
+    pub fn finish(&mut self) -> Option<u32> {
+        self.found.take().map(|(_, value)| value)
+    }
+
+    // Builds matcher of reversed patterns, becouse we need to match from the end of the line
+    pub fn reverse(&self) -> Self {
+        let patterns = self
+            .patterns
+            .iter()
+            .map(|(pattern, value)| (pattern.chars().rev().collect(), *value))
+            .collect();
+
+        Self::from_patterns(patterns)
+    }
+
+    pub fn reset(&mut self) {
+        self.current_node = 0;
+        self.position = 0;
+        self.found = None;
+    }
+}
+
+// Calculate the prefix function of a pattern, used in KMP algorithm
+fn pi(pattern: Vec<char>) -> Vec<usize> {
+    let mut result = vec![0];
+    let m = pattern.len();
+    let mut k = 0;
+
+    for q in 1..m {
+        while k > 0 && pattern[k] != pattern[q] {
+            k = result[k - 1];
+        }
+
+        if pattern[k] == pattern[q] {
+            k += 1;
+        }
+
+        result.push(k);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pi() {
+        let text: Vec<_> = "ababababca".chars().collect();
+        assert_eq!(pi(text), vec![0, 0, 1, 2, 3, 4, 5, 6, 0, 1]);
+    }
+
+    #[test]
+    fn test_pattern_matcher() {
+        let mut matcher = PatternMatcher::new("ababc", 1);
+        assert!(!matcher.matches('a'));
+        assert!(!matcher.matches('b'));
+        assert!(!matcher.matches('a'));
+        assert!(!matcher.matches('b'));
+        assert!(matcher.matches('c'));
+    }
+
+    #[test]
+    fn test_pattern_matcher_correctly_go_back_to_longest_preffix() {
+        let mut matcher = PatternMatcher::new("ababc", 1);
+        assert!(!matcher.matches('a'));
+        assert!(!matcher.matches('b'));
+        assert!(!matcher.matches('a'));
+        assert!(!matcher.matches('b'));
+
+        assert!(!matcher.matches('a')); // go back to 'aba'
+
+        assert!(!matcher.matches('b'));
+        assert!(matcher.matches('c'));
+    }
+
+    #[test]
+    fn test_multi_pattern_matcher_follows_failure_links() {
+        let mut matcher = MultiPatternMatcher::new(&[("nine", 9), ("ine", 7), ("one", 1)]);
+        assert_eq!(matcher.matches('n'), None);
+        assert_eq!(matcher.matches('i'), None);
+        assert_eq!(matcher.matches('n'), None); // go back to 'in'
+        assert_eq!(matcher.matches('i'), None);
+        assert_eq!(matcher.matches('o'), None);
+        assert_eq!(matcher.matches('n'), None);
+        assert_eq!(matcher.matches('e'), Some(1));
+    }
+
+    #[test]
+    fn test_multi_pattern_matcher_reports_suffix_pattern() {
+        let mut matcher = MultiPatternMatcher::new(&[("xnine", 9), ("ine", 7)]);
+        assert_eq!(matcher.matches('n'), None);
+        assert_eq!(matcher.matches('i'), None);
+        assert_eq!(matcher.matches('n'), None);
+        assert_eq!(matcher.matches('e'), Some(7));
+    }
+
+    #[test]
+    fn test_multi_pattern_matcher_finds_leftmost_match() {
+        let mut matcher = MultiPatternMatcher::new(&[("abcd", 1), ("bc", 2)]);
+        assert_eq!(matcher.matches('a'), None);
+        assert_eq!(matcher.matches('b'), None);
+        assert_eq!(matcher.matches('c'), None); // `abcd` may still end
+        assert_eq!(matcher.matches('d'), Some(1));
+
+        matcher.reset();
+        assert_eq!("abcx".chars().find_map(|c| matcher.matches(c)), Some(2));
+
+        matcher.reset();
+        assert_eq!("abc".chars().find_map(|c| matcher.matches(c)), None);
+        assert_eq!(matcher.finish(), Some(2));
+
+        let mut matcher = MultiPatternMatcher::new(&[("b", 2), ("ab", 1)]);
+        assert_eq!(matcher.matches('a'), None);
+        assert_eq!(matcher.matches('b'), Some(1));
+    }
+
+    #[test]
+    fn test_multi_pattern_matcher_reverse() {
+        let mut matcher = MultiPatternMatcher::new(&[("two", 2), ("one", 1)]).reverse();
+        assert_eq!(matcher.matches('e'), None);
+        assert_eq!(matcher.matches('n'), None);
+        assert_eq!(matcher.matches('o'), Some(1));
+        assert_eq!(matcher.matches('w'), None);
+        assert_eq!(matcher.matches('t'), Some(2));
+    }
+
+    #[test]
+    fn test_pattern_matcher_reverse_recalculates_pi() {
+        let matcher = PatternMatcher::new("aab", 1).reverse();
+        assert_eq!(matcher.pi, pi("baa".chars().collect()));
+
+        let mut matcher = matcher;
+        assert!(!matcher.matches('b'));
+        assert!(!matcher.matches('a'));
+        assert!(!matcher.matches('b')); // go back to 'b'
+        assert!(!matcher.matches('a'));
+        assert!(matcher.matches('a'));
+    }
+
+    // Simple xorshift generator, so property tests don't need any dependencies
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self, bound: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % bound as u64) as usize
+        }
+
+        fn string(&mut self, max_len: usize) -> String {
+            let len = self.next(max_len) + 1;
+            (0..len).map(|_| ['a', 'b', 'c'][self.next(3)]).collect()
+        }
+    }
+
+    // Value of the word that starts the most to the right, first word wins ties
+    fn rightmost_match(text: &str, words: &[String]) -> Option<u32> {
+        let mut matchers = words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| PatternMatcher::new(word, i as u32).reverse())
+            .collect::<Vec<_>>();
+
+        text.chars().rev().find_map(|c| {
+            matchers
+                .iter_mut()
+                .map(|m| if m.matches(c) { Some(m.value()) } else { None })
+                .fold(None, |found, value| found.or(value))
+        })
+    }
+
+    fn naive_rightmost_match(text: &str, words: &[String]) -> Option<u32> {
+        words
+            .iter()
+            .enumerate()
+            .filter_map(|(i, word)| text.rfind(word.as_str()).map(|start| (start, i)))
+            .max_by(|(a, i), (b, j)| a.cmp(b).then(j.cmp(i)))
+            .map(|(_, i)| i as u32)
+    }
+
+    // Value of the word that starts the most to the left, shorter word wins ties, then first one
+    fn naive_leftmost_match(text: &str, words: &[String]) -> Option<u32> {
+        words
+            .iter()
+            .enumerate()
+            .filter_map(|(i, word)| text.find(word.as_str()).map(|start| (start, word.len(), i)))
+            .min()
+            .map(|(_, _, i)| i as u32)
+    }
+
+    #[test]
+    fn test_multi_pattern_matcher_finds_leftmost_match_in_random_texts() {
+        let mut random = Random(0x9e3779b97f4a7c15);
+
+        for _ in 0..2000 {
+            let words = (0..random.next(4) + 1)
+                .map(|_| random.string(5))
+                .collect::<Vec<_>>();
+            let text = random.string(30);
+
+            let patterns = words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| (word.as_str(), i as u32))
+                .collect::<Vec<_>>();
+            let mut matcher = MultiPatternMatcher::new(&patterns);
+            let found = text
+                .chars()
+                .find_map(|c| matcher.matches(c))
+                .or_else(|| matcher.finish());
+
+            assert_eq!(
+                found,
+                naive_leftmost_match(&text, &words),
+                "text: {text}, words: {words:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_reversed_pattern_matchers_find_rightmost_match() {
+        let mut random = Random(0x2545f4914f6cdd1d);
+
+        for _ in 0..2000 {
+            let words = (0..random.next(4) + 1)
+                .map(|_| random.string(5))
+                .collect::<Vec<_>>();
+            let text = random.string(30);
+
+            assert_eq!(
+                rightmost_match(&text, &words),
+                naive_rightmost_match(&text, &words),
+                "text: {text}, words: {words:?}"
+            );
+        }
+    }
+}
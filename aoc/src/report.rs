@@ -0,0 +1,169 @@
+use crate::diagnostic::Diagnostic;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+// Everything that came out of solving one input, printed the same way by every binary
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    pub day: u32,
+    pub part: u32,
+    // `None` for stdin
+    pub file: Option<String>,
+    // `None` when solving failed, the reason is then in `errors`
+    pub answer: Option<String>,
+    // `None` for phases that didn't run, days that solve while reading only count parsing of lines
+    pub parse: Option<Duration>,
+    pub solve: Option<Duration>,
+    // in lenient mode all lines that were skipped
+    pub errors: Vec<Diagnostic>,
+    // problems that didn't stop solving
+    pub warnings: Vec<Diagnostic>,
+}
+
+impl Report {
+    pub fn new(day: u32, part: u32) -> Self {
+        Self {
+            day,
+            part,
+            file: None,
+            answer: None,
+            parse: None,
+            solve: None,
+            errors: vec![],
+            warnings: vec![],
+        }
+    }
+
+    pub fn failed(day: u32, part: u32, error: Diagnostic) -> Self {
+        Self {
+            errors: vec![error],
+            ..Self::new(day, part)
+        }
+    }
+
+    pub fn time(&self) -> Duration {
+        self.parse.unwrap_or_default() + self.solve.unwrap_or_default()
+    }
+
+    pub fn in_file(self, file: &str) -> Self {
+        let in_file = |diagnostics: Vec<Diagnostic>| {
+            diagnostics
+                .into_iter()
+                .map(|diagnostic| diagnostic.in_file(file))
+                .collect()
+        };
+
+        Self {
+            file: Some(file.to_string()),
+            errors: in_file(self.errors),
+            warnings: in_file(self.warnings),
+            ..self
+        }
+    }
+
+    // Like `{"day":1,"part":2,"file":null,"answer":"281","parse_ns":10,"solve_ns":20,"errors":[]}`
+    pub fn to_json(&self) -> String {
+        let errors = self
+            .errors
+            .iter()
+            .map(diagnostic_json)
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(
+            "{{\"day\":{},\"part\":{},\"file\":{},\"answer\":{},\"parse_ns\":{},\"solve_ns\":{},\"errors\":[{}]}}",
+            self.day,
+            self.part,
+            or_null(self.file.as_deref().map(json_string)),
+            or_null(self.answer.as_deref().map(json_string)),
+            or_null(self.parse.map(|time| time.as_nanos())),
+            or_null(self.solve.map(|time| time.as_nanos())),
+            errors
+        )
+    }
+}
+
+fn or_null(value: Option<impl ToString>) -> String {
+    value.map_or("null".to_string(), |value| value.to_string())
+}
+
+fn diagnostic_json(diagnostic: &Diagnostic) -> String {
+    format!(
+        "{{\"file\":{},\"line\":{},\"columns\":{},\"message\":{}}}",
+        or_null(diagnostic.file.as_deref().map(json_string)),
+        or_null(diagnostic.line),
+        or_null(
+            diagnostic
+                .columns
+                .as_ref()
+                .map(|columns| format!("[{},{}]", columns.start, columns.end))
+        ),
+        json_string(&diagnostic.message)
+    )
+}
+
+pub fn json_string(text: &str) -> String {
+    let mut result = String::from("\"");
+
+    for c in text.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a \"b\"\n\\"), "\"a \\\"b\\\"\\n\\\\\"");
+        assert_eq!(json_string("\u{1}"), "\"\\u0001\"");
+    }
+
+    #[test]
+    fn test_to_json() {
+        let report = Report {
+            answer: Some("281".to_string()),
+            parse: Some(Duration::from_nanos(10)),
+            solve: Some(Duration::from_nanos(20)),
+            ..Report::new(1, 2)
+        };
+
+        assert_eq!(
+            report.to_json(),
+            "{\"day\":1,\"part\":2,\"file\":null,\"answer\":\"281\",\"parse_ns\":10,\"solve_ns\":20,\"errors\":[]}"
+        );
+
+        let report = Report::failed(
+            4,
+            1,
+            Diagnostic::new("`x` is not a number")
+                .at_line(3)
+                .at_columns(5..6),
+        )
+        .in_file("input.txt");
+
+        assert_eq!(
+            report.to_json(),
+            "{\"day\":4,\"part\":1,\"file\":\"input.txt\",\"answer\":null,\"parse_ns\":null,\"solve_ns\":null,\"errors\":[\
+             {\"file\":\"input.txt\",\"line\":3,\"columns\":[5,6],\"message\":\"`x` is not a number\"}]}"
+        );
+    }
+}
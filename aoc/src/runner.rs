@@ -0,0 +1,312 @@
+use crate::bench::{self, Measurement};
+use crate::day1::{self, Day1};
+use crate::day2::Day2;
+use crate::day3::Day3;
+use crate::day4::Day4;
+use crate::day5::{self, Day5};
+use crate::diagnostic::Diagnostic;
+use crate::normalize::normalize;
+use crate::report::Report;
+use crate::solution::{self, Options, Part, Solution};
+use std::fs;
+use std::io::{self, BufRead};
+use std::path::Path;
+
+// One part of a day with its input and answer types hidden, so every day fits in one table,
+// reports are made while reading the input, other methods take all of it, every input is
+// normalized first
+pub trait Solver {
+    fn solve(&self, input: &str) -> Result<String, Diagnostic>;
+    fn report(&self, reader: &mut dyn BufRead, options: &Options) -> io::Result<(Report, String)>;
+    fn bench(&self, input: &str, runs: usize) -> Result<Measurement, Diagnostic>;
+}
+
+struct PartOf<S> {
+    solution: S,
+    part: Part,
+}
+
+impl<S: Solution> Solver for PartOf<S> {
+    fn solve(&self, input: &str) -> Result<String, Diagnostic> {
+        let (input, _) = normalize(input);
+
+        self.solution
+            .solve(&input, self.part)
+            .map(|answer| answer.to_string())
+            .map_err(Into::into)
+    }
+
+    fn report(&self, reader: &mut dyn BufRead, options: &Options) -> io::Result<(Report, String)> {
+        solution::report_reader(&self.solution, self.part, reader, options)
+    }
+
+    fn bench(&self, input: &str, runs: usize) -> Result<Measurement, Diagnostic> {
+        let (input, _) = normalize(input);
+
+        bench::measure(&self.solution, self.part, &input, runs).map_err(Into::into)
+    }
+}
+
+pub const DAYS: u32 = 5;
+pub const PARTS: u32 = 2;
+
+// Every day and part that has a solver, in order
+pub fn all() -> impl Iterator<Item = (u32, u32)> {
+    (1..=DAYS).flat_map(|day| (1..=PARTS).map(move |part| (day, part)))
+}
+
+// `strict` is the same as `--strict` of days 3 and 5, other days don't have it
+pub fn solver(day: u32, part: u32, strict: bool) -> Option<Box<dyn Solver>> {
+    let part = Part::from_number(part)?;
+
+    match day {
+        1 => Some(boxed(Day1, part)),
+        2 => Some(boxed(Day2::default(), part)),
+        3 => Some(boxed(Day3 { strict }, part)),
+        4 => Some(boxed(Day4, part)),
+        5 => Some(boxed(Day5 { strict }, part)),
+        _ => None,
+    }
+}
+
+fn boxed(solution: impl Solution + 'static, part: Part) -> Box<dyn Solver> {
+    Box::new(PartOf { solution, part })
+}
+
+type PartFn<S> =
+    fn(&<S as Solution>::Input) -> Result<<S as Solution>::Answer, <S as Solution>::Error>;
+
+// Solution of a day with one part solved by `solve` instead, input is parsed the same way
+struct WithPart<S: Solution> {
+    solution: S,
+    part: Part,
+    solve: PartFn<S>,
+}
+
+impl<S: Solution> Solution for WithPart<S> {
+    const DAY: u32 = S::DAY;
+
+    type Input = S::Input;
+    type Answer = S::Answer;
+    type Error = S::Error;
+
+    fn parse(&self, input: &str) -> Result<S::Input, S::Error> {
+        self.solution.parse(input)
+    }
+
+    fn part1(&self, input: &S::Input) -> Result<S::Answer, S::Error> {
+        match self.part {
+            Part::One => (self.solve)(input),
+            Part::Two => self.solution.part1(input),
+        }
+    }
+
+    fn part2(&self, input: &S::Input) -> Result<S::Answer, S::Error> {
+        match self.part {
+            Part::One => self.solution.part2(input),
+            Part::Two => (self.solve)(input),
+        }
+    }
+
+    fn warnings(&self, input: &S::Input) -> Vec<Diagnostic> {
+        self.solution.warnings(input)
+    }
+}
+
+fn variant<S: Solution + 'static>(solution: S, part: Part, solve: PartFn<S>) -> Box<dyn Solver> {
+    boxed(
+        WithPart {
+            solution,
+            part,
+            solve,
+        },
+        part,
+    )
+}
+
+// Earlier solutions of a part, kept so benchmarks can compare them with the current one
+pub struct Variant {
+    pub day: u32,
+    pub part: u32,
+    pub name: &'static str,
+    pub solver: Box<dyn Solver>,
+}
+
+pub fn variants() -> Vec<Variant> {
+    vec![
+        Variant {
+            day: 1,
+            part: 2,
+            name: "kmp",
+            solver: variant(Day1, Part::Two, |text: &String| day1::part2_kmp(text)),
+        },
+        Variant {
+            day: 5,
+            part: 2,
+            name: "brute force",
+            solver: variant(Day5::default(), Part::Two, day5::part2_brute_force),
+        },
+    ]
+}
+
+// Binaries of every day are in the workspace next to this crate, so their files are found from
+// any directory
+fn in_workspace(path: String) -> String {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("crate is in a workspace");
+
+    root.join(path).display().to_string()
+}
+
+// Where the binary of given day and part keeps its input
+pub fn default_input(day: u32, part: u32) -> String {
+    in_workspace(format!("d{}p{}/input.txt", day, part))
+}
+
+// Accepted answer for the default input, kept next to it
+pub fn answer_path(day: u32, part: u32) -> String {
+    in_workspace(format!("d{}p{}/answer.txt", day, part))
+}
+
+pub fn recorded_answer(day: u32, part: u32) -> Option<String> {
+    let answer = fs::read_to_string(answer_path(day, part)).ok()?;
+    Some(answer.trim().to_string())
+}
+
+pub fn record_answer(day: u32, part: u32, answer: &str) -> std::io::Result<()> {
+    fs::write(answer_path(day, part), format!("{}\n", answer))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Correct,
+    Wrong { expected: String },
+    NotRecorded,
+}
+
+pub fn verdict(answer: &str, expected: Option<&str>) -> Verdict {
+    match expected {
+        Some(expected) if expected == answer => Verdict::Correct,
+        Some(expected) => Verdict::Wrong {
+            expected: expected.to_string(),
+        },
+        None => Verdict::NotRecorded,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_solver() {
+        let solver = solver(1, 1, false).unwrap();
+
+        assert_eq!(solver.solve("1abc2\npqr3stu8vwx"), Ok("50".to_string()));
+        assert!(solver.solve("abc").is_err());
+    }
+
+    #[test]
+    fn test_solver_normalizes_input() {
+        // `\r` would be a symbol next to the number
+        assert_eq!(
+            solver(3, 1, false).unwrap().solve("12\r\n"),
+            Ok("0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_strict_solver() {
+        let ragged = "1*\n.";
+
+        assert_eq!(
+            solver(3, 1, false).unwrap().solve(ragged),
+            Ok("1".to_string())
+        );
+        assert!(solver(3, 1, true).unwrap().solve(ragged).is_err());
+    }
+
+    fn report_of(day: u32, input: &str, lenient: bool) -> Report {
+        let options = Options {
+            lenient,
+            ..Options::default()
+        };
+
+        let (report, _) = solver(day, 1, false)
+            .unwrap()
+            .report(&mut input.as_bytes(), &options)
+            .unwrap();
+
+        report
+    }
+
+    #[test]
+    fn test_report() {
+        let report = report_of(1, "12\nabc", true);
+
+        assert_eq!(report.answer, Some("12".to_string()));
+        assert!(report.parse.is_some());
+        assert_eq!(
+            report.errors,
+            vec![Diagnostic::new("line has no digit")
+                .at_line(2)
+                .with_snippet("abc")]
+        );
+
+        let report = report_of(1, "12\nabc", false);
+
+        assert_eq!(report.answer, None);
+        assert_eq!(report.errors.len(), 1);
+
+        // Days that don't read the input line by line stop on errors also in lenient mode
+        let report = report_of(3, "...\n..", true);
+
+        assert_eq!(report.answer, Some("0".to_string()));
+        assert!(report.parse.is_some());
+    }
+
+    #[test]
+    fn test_every_day_has_a_solver() {
+        for day in 1..=DAYS {
+            for part in 1..=PARTS {
+                assert!(super::solver(day, part, false).is_some());
+            }
+        }
+
+        assert!(super::solver(DAYS + 1, 1, false).is_none());
+        assert!(super::solver(1, PARTS + 1, false).is_none());
+        assert_eq!(all().count(), (DAYS * PARTS) as usize);
+    }
+
+    #[test]
+    fn test_variants_solve_like_current_solvers() {
+        let inputs = [
+            (1, "two1nine\nxtwone3four\nzoneight234"),
+            (
+                5,
+                "seeds: 79 14 55 13\n\nseed-to-location map:\n50 98 2\n52 50 48",
+            ),
+        ];
+
+        for variant in variants() {
+            let (_, input) = inputs.iter().find(|(day, _)| *day == variant.day).unwrap();
+            let current = solver(variant.day, variant.part, false).unwrap();
+
+            assert_eq!(variant.solver.solve(input), current.solve(input));
+            assert!(variant.solver.bench(input, 1).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_verdict() {
+        assert_eq!(verdict("42", Some("42")), Verdict::Correct);
+        assert_eq!(
+            verdict("42", Some("24")),
+            Verdict::Wrong {
+                expected: "24".to_string()
+            }
+        );
+        assert_eq!(verdict("42", None), Verdict::NotRecorded);
+    }
+}
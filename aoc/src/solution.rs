@@ -0,0 +1,508 @@
+use crate::diagnostic::Diagnostic;
+use crate::input;
+use crate::normalize::Normalizing;
+use crate::report::{Format, Report};
+use std::fmt::{Debug, Display};
+use std::io::{self, BufRead, Read};
+use std::ops::ControlFlow;
+use std::process;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn from_number(part: u32) -> Option<Self> {
+        match part {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+
+    pub fn number(&self) -> u32 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+// What solving while reading found
+#[derive(Debug, PartialEq)]
+pub struct Solved<A, E> {
+    // `None` when solving stopped before the end of the input
+    pub answer: Option<A>,
+    // lines that were skipped, every one with its text, so it can be shown after the input is gone
+    pub rejected: Vec<(E, String)>,
+    // part of the time that was spent parsing lines
+    pub parse: Duration,
+}
+
+// Answer of lenient solving with the lines that were skipped
+pub type Lenient<A, E> = (Option<A>, Vec<E>);
+
+// Keeps track of lines that were rejected and of time spent parsing while solving line by line
+pub struct Solving<E> {
+    lenient: bool,
+    stopped: bool,
+    rejected: Vec<(E, String)>,
+    parse: Duration,
+}
+
+impl<E> Solving<E> {
+    pub fn new(lenient: bool) -> Self {
+        Self {
+            lenient,
+            stopped: false,
+            rejected: vec![],
+            parse: Duration::ZERO,
+        }
+    }
+
+    // Time spent in `f` is counted as parse time
+    pub fn parse<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let parsed = f();
+        self.parse += start.elapsed();
+
+        parsed
+    }
+
+    // Line is skipped in lenient mode, otherwise solving stops on it
+    pub fn reject(&mut self, e: E, line: &str) -> ControlFlow<()> {
+        if self.lenient {
+            self.rejected.push((e, line.to_string()));
+            ControlFlow::Continue(())
+        } else {
+            self.stop(e, line)
+        }
+    }
+
+    // Stops also in lenient mode, for errors after which the answer can't be trusted
+    pub fn stop(&mut self, e: E, line: &str) -> ControlFlow<()> {
+        self.rejected.push((e, line.to_string()));
+        self.stopped = true;
+
+        ControlFlow::Break(())
+    }
+
+    pub fn finish<A>(self, answer: A) -> Solved<A, E> {
+        Solved {
+            answer: (!self.stopped).then_some(answer),
+            rejected: self.rejected,
+            parse: self.parse,
+        }
+    }
+}
+
+// Every day parses its input once, both parts are then solved from the parsed input
+pub trait Solution {
+    const DAY: u32;
+
+    type Input;
+    type Answer: Display;
+    type Error: Debug + Into<Diagnostic>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Self::Error>;
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer, Self::Error>;
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer, Self::Error>;
+
+    fn solve_part(&self, input: &Self::Input, part: Part) -> Result<Self::Answer, Self::Error> {
+        match part {
+            Part::One => self.part1(input),
+            Part::Two => self.part2(input),
+        }
+    }
+
+    fn solve(&self, input: &str, part: Part) -> Result<Self::Answer, Self::Error> {
+        self.solve_part(&self.parse(input)?, part)
+    }
+
+    // Problems found while parsing that don't stop solving
+    fn warnings(&self, _input: &Self::Input) -> Vec<Diagnostic> {
+        vec![]
+    }
+
+    // Skips lines that can't be read instead of stopping on the first one, answer is found for
+    // the rest of them, `None` when the day doesn't read its input line by line
+    fn solve_lenient(&self, input: &str, part: Part) -> Option<Lenient<Self::Answer, Self::Error>> {
+        let solved = self
+            .solve_reader(&mut input.as_bytes(), part, true)?
+            .expect("reading from memory doesn't fail");
+
+        Some((
+            solved.answer,
+            solved.rejected.into_iter().map(|(e, _)| e).collect(),
+        ))
+    }
+
+    // Solves while reading the input one line at a time, without keeping all of it in memory,
+    // returns the answer with the wrong lines, when not `lenient` it stops on the first one,
+    // `None` when the day doesn't read its input line by line
+    fn solve_reader(
+        &self,
+        _reader: &mut dyn BufRead,
+        _part: Part,
+        _lenient: bool,
+    ) -> Option<io::Result<Solved<Self::Answer, Self::Error>>> {
+        None
+    }
+}
+
+// Flags understood by every binary
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    // days that can't skip wrong lines still stop on the first one
+    pub lenient: bool,
+    // days 3 and 5 check their input more carefully
+    pub strict: bool,
+    // changes made to the input while normalizing it are shown as warnings
+    pub report_normalization: bool,
+    pub format: Format,
+}
+
+impl Options {
+    // Takes the flags out of `args`, the rest of them is left for the day
+    pub fn take(args: &mut Vec<String>) -> Result<Self, String> {
+        let lenient = input::take_flag(args, "--lenient");
+        let strict = input::take_flag(args, "--strict");
+        let report_normalization = input::take_flag(args, "--report-normalization");
+
+        let format = match args.iter().position(|arg| arg == "--format") {
+            None => Format::Text,
+            Some(i) if i + 1 < args.len() => {
+                let format = match args[i + 1].as_str() {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    format => return Err(format!("Unknown format: {}", format)),
+                };
+
+                args.drain(i..=i + 1);
+                format
+            }
+            Some(_) => return Err("Missing format after --format".to_string()),
+        };
+
+        Ok(Self {
+            lenient,
+            strict,
+            report_normalization,
+            format,
+        })
+    }
+
+    // Exits when flags are wrong, returns arguments that are left for the day
+    pub fn from_args() -> (Self, Vec<String>) {
+        let mut args = input::args();
+        let options = Self::take(&mut args).unwrap_or_else(|e| input::exit_with_error(e));
+
+        (options, args)
+    }
+}
+
+// Parse time is the part of `elapsed` that was spent parsing lines, rejected lines are shown with
+// their text
+fn solved_report<S: Solution>(
+    part: Part,
+    solved: Solved<S::Answer, S::Error>,
+    elapsed: Duration,
+) -> Report {
+    let mut report = Report::new(S::DAY, part.number());
+
+    report.parse = Some(solved.parse);
+    report.solve = Some(elapsed.saturating_sub(solved.parse));
+    report.answer = solved.answer.map(|answer| answer.to_string());
+    report.errors = solved
+        .rejected
+        .into_iter()
+        .map(|(e, text)| Into::<Diagnostic>::into(e).with_snippet(text))
+        .collect();
+
+    report
+}
+
+// Times parsing and solving on their own, errors are converted to diagnostics
+pub fn report<S: Solution>(solution: &S, part: Part, input: &str, lenient: bool) -> Report {
+    let mut report = Report::new(S::DAY, part.number());
+
+    if lenient {
+        let start = Instant::now();
+
+        if let Some(solved) = solution.solve_reader(&mut input.as_bytes(), part, true) {
+            let solved = solved.expect("reading from memory doesn't fail");
+            return solved_report::<S>(part, solved, start.elapsed());
+        }
+    }
+
+    let start = Instant::now();
+    let parsed = solution.parse(input);
+    report.parse = Some(start.elapsed());
+
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            report.errors.push(e.into());
+            return report;
+        }
+    };
+
+    report.warnings = solution.warnings(&parsed);
+
+    let start = Instant::now();
+    let answer = solution.solve_part(&parsed, part);
+    report.solve = Some(start.elapsed());
+
+    match answer {
+        Ok(answer) => report.answer = Some(answer.to_string()),
+        Err(e) => report.errors.push(e.into()),
+    }
+
+    report
+}
+
+// In text format answer goes to stdout and errors to stderr, in json everything goes to stdout,
+// answers are prefixed with the file they are for when there are `several` of them
+pub fn print_report(report: &Report, source: &str, format: Format, several: bool) {
+    for warning in report.warnings.iter() {
+        eprintln!("Warning: {}", warning);
+    }
+
+    match format {
+        Format::Json => println!("{}", report.to_json()),
+        Format::Text if report.answer.is_none() => {
+            for error in report.errors.iter() {
+                eprint!("{}", error.render(source));
+            }
+        }
+        Format::Text => {
+            let answer = report.answer.as_deref().unwrap_or_default();
+
+            if several {
+                println!(
+                    "{}: {}",
+                    report.file.as_deref().unwrap_or(input::STDIN),
+                    answer
+                );
+            } else {
+                println!("{}", answer);
+            }
+
+            report_rejected(report.errors.iter().cloned(), source);
+        }
+    }
+}
+
+// Input is normalized first, changes made to it are warnings when they are reported, days that
+// read their input line by line solve it while it's being read, only the lines that were rejected
+// are kept to be shown with errors
+pub fn report_reader<S: Solution>(
+    solution: &S,
+    part: Part,
+    reader: &mut dyn BufRead,
+    options: &Options,
+) -> io::Result<(Report, String)> {
+    let mut reader = Normalizing::new(reader);
+    let start = Instant::now();
+
+    let (mut report, source) = match solution.solve_reader(&mut reader, part, options.lenient) {
+        Some(solved) => (
+            solved_report::<S>(part, solved?, start.elapsed()),
+            String::new(),
+        ),
+        None => {
+            let mut source = String::new();
+            reader.read_to_string(&mut source)?;
+
+            (report(solution, part, &source, options.lenient), source)
+        }
+    };
+
+    if options.report_normalization {
+        report.warnings.splice(0..0, reader.changes().diagnostics());
+    }
+
+    Ok((report, source))
+}
+
+// Failing to read the input from `path` is reported as an error
+pub fn report_file<S: Solution>(
+    solution: &S,
+    part: Part,
+    path: &str,
+    options: &Options,
+) -> (Report, String) {
+    let reported = input::open_input(path)
+        .and_then(|mut reader| report_reader(solution, part, &mut reader, options));
+
+    let (report, source) = match reported {
+        Ok(reported) => reported,
+        Err(e) => {
+            let error = Diagnostic::new(e.to_string());
+            (Report::failed(S::DAY, part.number(), error), String::new())
+        }
+    };
+
+    match path {
+        input::STDIN => (report, source),
+        path => (report.in_file(path), source),
+    }
+}
+
+// Output shared by all binaries, one answer for every path, exits with 1 when any has no answer
+pub fn run(solution: &impl Solution, part: Part, options: &Options, paths: &[String]) {
+    let mut failed = false;
+
+    for path in paths {
+        let (report, source) = report_file(solution, part, path, options);
+
+        print_report(&report, &source, options.format, paths.len() > 1);
+        failed |= report.answer.is_none();
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+// Every rejected line with its reason
+pub fn report_rejected(rejected: impl Iterator<Item = Diagnostic>, source: &str) {
+    let mut count = 0;
+
+    for diagnostic in rejected {
+        eprint!("{}", diagnostic.render(source));
+        count += 1;
+    }
+
+    if count > 0 {
+        eprintln!("Rejected lines: {}", count);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day1::Day1;
+    use crate::day2::Day2;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_options_take() {
+        let mut rest = args(&["red=1", "--format", "json", "--lenient", "blue=2"]);
+
+        assert_eq!(
+            Options::take(&mut rest),
+            Ok(Options {
+                lenient: true,
+                strict: false,
+                report_normalization: false,
+                format: Format::Json,
+            })
+        );
+        assert_eq!(rest, args(&["red=1", "blue=2"]));
+
+        let mut rest = args(&["--report-normalization", "a.txt", "--strict"]);
+
+        assert_eq!(
+            Options::take(&mut rest),
+            Ok(Options {
+                lenient: false,
+                strict: true,
+                report_normalization: true,
+                format: Format::Text,
+            })
+        );
+        assert_eq!(rest, args(&["a.txt"]));
+
+        assert_eq!(Options::take(&mut args(&[])), Ok(Options::default()));
+        assert!(Options::take(&mut args(&["--format", "xml"])).is_err());
+        assert!(Options::take(&mut args(&["--format"])).is_err());
+    }
+
+    #[test]
+    fn test_report_file_that_cant_be_read() {
+        let (report, source) = report_file(&Day1, Part::One, "missing.txt", &Options::default());
+
+        assert_eq!(report.file, Some("missing.txt".to_string()));
+        assert_eq!(report.answer, None);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(source, "");
+    }
+
+    #[test]
+    fn test_report_reader_normalizes_input() {
+        let input = "1abc2\r\n3 \n\n";
+        let reported = Options {
+            report_normalization: true,
+            ..Options::default()
+        };
+
+        let (report, _) =
+            report_reader(&Day1, Part::One, &mut input.as_bytes(), &Options::default()).unwrap();
+
+        assert_eq!(report.answer, Some("45".to_string()));
+        assert!(report.warnings.is_empty());
+
+        let (report, _) =
+            report_reader(&Day1, Part::One, &mut input.as_bytes(), &reported).unwrap();
+
+        assert_eq!(report.answer, Some("45".to_string()));
+        assert_eq!(report.warnings.len(), 3);
+    }
+
+    #[test]
+    fn test_streamed_json_report_has_parse_time() {
+        let json = Options {
+            format: Format::Json,
+            ..Options::default()
+        };
+
+        let lenient = Options {
+            lenient: true,
+            format: Format::Json,
+            ..Options::default()
+        };
+        let input = "Game 1: 1 red\nGame 2: red\n";
+
+        for options in [json, lenient] {
+            let (report, source) =
+                report_reader(&Day2::default(), Part::One, &mut input.as_bytes(), &options)
+                    .unwrap();
+
+            assert!(report.parse.is_some());
+            assert!(!report.to_json().contains("\"parse_ns\":null"));
+            // input is still read line by line
+            assert_eq!(source, "");
+        }
+    }
+
+    #[test]
+    fn test_report_reader_keeps_rejected_lines() {
+        let input = "Game 1: 1 red\nGame 2: red\nGame 3: 2 blue\n";
+        let lenient = Options {
+            lenient: true,
+            ..Options::default()
+        };
+
+        let (report, source) =
+            report_reader(&Day2::default(), Part::One, &mut input.as_bytes(), &lenient).unwrap();
+
+        assert_eq!(report.answer, Some("4".to_string()));
+        assert_eq!(source, "");
+        assert_eq!(
+            report.errors[0].render(&source),
+            "error: malformed game
+ --> <input>:2:9
+  |
+2 | Game 2: red
+  |         ^^^
+"
+        );
+    }
+}
@@ -0,0 +1,23 @@
+use aoc::runner::{self, Verdict};
+use std::fs;
+
+// Every solver still gives the recorded answer for its `input.txt`, run `aoc record` to update
+#[test]
+fn test_recorded_answers() {
+    for (day, part) in runner::all() {
+        let input = fs::read_to_string(runner::default_input(day, part)).unwrap();
+        let answer = runner::solver(day, part, false)
+            .unwrap()
+            .solve(&input)
+            .unwrap();
+        let expected = runner::recorded_answer(day, part);
+
+        assert_eq!(
+            runner::verdict(&answer, expected.as_deref()),
+            Verdict::Correct,
+            "day {} part {}",
+            day,
+            part
+        );
+    }
+}